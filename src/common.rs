@@ -1,13 +1,61 @@
-use nalgebra::{Dim, Matrix, Matrix2, Scalar, Storage, Vector2};
-use nalgebra::{SVector, Vector3};
+use nalgebra::{DVector, SVector, Vector3};
+use nalgebra::{Matrix2, Scalar, Vector2};
 use num::rational::Ratio;
 use num::{Rational64, Signed};
 use std::fmt::Debug;
 use std::hash::Hash;
-use std::ops::{Index, IndexMut};
+use std::io::{self, BufRead};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut, RangeBounds};
 use std::str::FromStr;
 use thiserror::Error;
 
+mod aabb;
+mod answer;
+mod bitset;
+mod checked;
+mod compression;
+mod dag;
+mod digits;
+mod digraph;
+mod disjoint_set;
+mod dlx;
+mod dominators;
+mod fast_parse;
+mod geometry;
+mod gf2;
+mod ilp;
+mod intern;
+mod interval;
+mod kd_tree;
+mod memo;
+mod metric;
+mod point_loop;
+mod polygon;
+
+pub use aabb::*;
+pub use answer::*;
+pub use bitset::*;
+pub use checked::*;
+pub use compression::*;
+pub use dag::*;
+pub use digits::*;
+pub use digraph::*;
+pub use disjoint_set::*;
+pub use dlx::*;
+pub use dominators::*;
+pub use fast_parse::*;
+pub use geometry::*;
+pub use gf2::*;
+pub use ilp::*;
+pub use intern::*;
+pub use interval::*;
+pub use kd_tree::*;
+pub use memo::*;
+pub use metric::*;
+pub use point_loop::*;
+pub use polygon::*;
+
 pub type Rational128 = Ratio<i128>;
 pub type Vec2i = Vector2<i64>;
 pub type Vec2r = Vector2<Rational64>;
@@ -20,15 +68,9 @@ pub type Mat2i = Matrix2<i64>;
 pub type Mat2r = Matrix2<Rational64>;
 pub type Mat2r128 = Matrix2<Rational128>;
 
-pub fn lp1_norm<T: Scalar + Signed, R: Dim, C: Dim, S: Storage<T, R, C>>(
-    v: &Matrix<T, R, C, S>,
-) -> T {
-    match v.shape() {
-        (1, _) | (_, 1) => v.fold(T::zero(), |a, e| a + e.abs()),
-        _ => {
-            panic!("lp1 norm is only implemented for vectors")
-        }
-    }
+/// The sum of absolute values, see [`Manhattan`] for an overflow safe distance on integer vectors.
+pub fn lp1_norm<T: Scalar + Signed, const D: usize>(v: &SVector<T, D>) -> T {
+    v.fold(T::zero(), |a, e| a + e.abs())
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -153,6 +195,18 @@ impl<T: Clone> Grid<T> {
     }
 }
 
+impl<T> Grid<T> {
+    /// Creates a grid from its elements in row-major order.
+    pub fn from_vec(size_x: usize, size_y: usize, grid: Vec<T>) -> Self {
+        assert_eq!(grid.len(), size_x * size_y, "non rectangular grid");
+        Self {
+            size_x,
+            size_y,
+            grid,
+        }
+    }
+}
+
 impl<T: Default + Clone> Grid<T> {
     pub fn new_from_default(size_x: usize, size_y: usize) -> Self {
         Self::new_from_element(size_x, size_y, T::default())
@@ -160,8 +214,16 @@ impl<T: Default + Clone> Grid<T> {
 }
 
 impl<T> Grid<T> {
+    /// The box of all valid positions, which is empty for an empty grid.
+    pub fn bounds(&self) -> Aabb2<i64> {
+        Aabb2::new(
+            Vec2i::zeros(),
+            Vec2i::new(self.size_x as i64 - 1, self.size_y as i64 - 1),
+        )
+    }
+
     pub fn in_bounds(&self, pos: &Vec2i) -> bool {
-        pos.x >= 0 && (pos.x as usize) < self.size_x && pos.y >= 0 && (pos.y as usize) < self.size_y
+        self.bounds().contains(pos)
     }
 
     pub fn mod_get(&self, pos: &Vec2i) -> &T {
@@ -226,7 +288,10 @@ pub fn parse_split_whitespace<T: FromStr, B: FromIterator<T>>(
     s.split_whitespace().map(str::parse).collect()
 }
 
-pub fn parse_split<T: FromStr, B: FromIterator<T>>(
+/// Lenient parsing of the fields separated by `pat`, empty fields are silently skipped.
+///
+/// Prefer [`parse_split_strict`] unless the input is known to contain empty fields.
+pub fn parse_split_lenient<T: FromStr, B: FromIterator<T>>(
     s: &str,
     pat: char,
 ) -> Result<B, <T as FromStr>::Err> {
@@ -237,7 +302,12 @@ pub fn parse_split<T: FromStr, B: FromIterator<T>>(
         .collect()
 }
 
-pub fn parse_lines<T: FromStr, B: FromIterator<T>>(s: &str) -> Result<B, <T as FromStr>::Err> {
+/// Lenient parsing of all lines, blank lines are silently skipped.
+///
+/// Prefer [`parse_lines_strict`] unless the input is known to contain blank lines.
+pub fn parse_lines_lenient<T: FromStr, B: FromIterator<T>>(
+    s: &str,
+) -> Result<B, <T as FromStr>::Err> {
     s.lines()
         .map(str::trim)
         .filter(|l| !l.is_empty())
@@ -245,36 +315,535 @@ pub fn parse_lines<T: FromStr, B: FromIterator<T>>(s: &str) -> Result<B, <T as F
         .collect::<Result<_, _>>()
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StrictParseError<T> {
+    #[error("empty field at position {pos}")]
+    EmptyField { pos: usize },
+    #[error("unexpected blank line {line}")]
+    BlankLine { line: usize },
+    #[error("trailing garbage at position {pos}")]
+    TrailingGarbage { pos: usize },
+    #[error("parse error in line {line} at position {pos}")]
+    ParseError {
+        line: usize,
+        pos: usize,
+        #[source]
+        source: T,
+    },
+}
+
+/// Byte offset of the first non-whitespace character of `part`, which has to be a slice of `s`.
+fn trimmed_offset(s: &str, part: &str) -> usize {
+    let trimmed = part.trim_start();
+    trimmed.as_ptr() as usize - s.as_ptr() as usize
+}
+
+/// Strict variant of [`parse_split_lenient`] for a single line of fields.
+///
+/// Whitespace around fields and around the whole input is allowed,
+/// but empty fields and any further lines are reported with their byte offset.
+pub fn parse_split_strict<T: FromStr, B: FromIterator<T>>(
+    s: &str,
+    pat: char,
+) -> Result<B, StrictParseError<<T as FromStr>::Err>> {
+    let content = s.trim();
+    if let Some(idx) = content.find('\n') {
+        return Err(StrictParseError::TrailingGarbage {
+            pos: trimmed_offset(s, &content[idx..]),
+        });
+    }
+
+    content
+        .split(pat)
+        .map(|field| {
+            let pos = trimmed_offset(s, field);
+            let field = field.trim();
+            if field.is_empty() {
+                return Err(StrictParseError::EmptyField { pos });
+            }
+            field
+                .parse()
+                .map_err(|source| StrictParseError::ParseError {
+                    line: 1,
+                    pos,
+                    source,
+                })
+        })
+        .collect()
+}
+
+/// Strict variant of [`parse_lines_lenient`].
+///
+/// Only trailing blank lines at the end of the input are allowed,
+/// every other blank line is reported with its line number.
+pub fn parse_lines_strict<T: FromStr, B: FromIterator<T>>(
+    s: &str,
+) -> Result<B, StrictParseError<<T as FromStr>::Err>> {
+    let content = s.trim_end();
+    content
+        .split('\n')
+        .take_while(|_| !content.is_empty())
+        .enumerate()
+        .map(|(idx, l)| {
+            let line = idx + 1;
+            let pos = trimmed_offset(s, l);
+            let l = l.trim();
+            if l.is_empty() {
+                return Err(StrictParseError::BlankLine { line });
+            }
+            l.parse()
+                .map_err(|source| StrictParseError::ParseError { line, pos, source })
+        })
+        .collect()
+}
+
 #[derive(Error, Debug)]
+pub enum ReadError<T> {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("parse error in line {line}")]
+    Parse {
+        line: usize,
+        #[source]
+        source: T,
+    },
+}
+
+impl<T> ReadError<T> {
+    pub fn map_parse<U>(self, f: impl FnOnce(T) -> U) -> ReadError<U> {
+        match self {
+            Self::Io(e) => ReadError::Io(e),
+            Self::Parse { line, source } => ReadError::Parse {
+                line,
+                source: f(source),
+            },
+        }
+    }
+}
+
+/// Streaming variant of [`parse_lines_lenient`], see [`parse_lines_from`] and [`parse_block_from`].
+pub struct ReadLines<T, R> {
+    reader: R,
+    buf: String,
+    line: usize,
+    block: bool,
+    started: bool,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T: FromStr, R: BufRead> Iterator for ReadLines<T, R> {
+    type Item = Result<T, ReadError<<T as FromStr>::Err>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buf.clear();
+            match self.reader.read_line(&mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e.into())),
+            }
+            self.line += 1;
+
+            let l = self.buf.trim();
+            if l.is_empty() {
+                if self.block && self.started {
+                    return None;
+                }
+                continue;
+            }
+
+            self.started = true;
+            let line = self.line;
+            return Some(
+                l.parse()
+                    .map_err(|source| ReadError::Parse { line, source }),
+            );
+        }
+    }
+}
+
+/// Parses the lines of `reader` one at a time, skipping blank lines.
+///
+/// Only a single line is kept in memory, so this works for inputs of any size.
+pub fn parse_lines_from<T: FromStr, R: BufRead>(reader: R) -> ReadLines<T, R> {
+    ReadLines {
+        reader,
+        buf: String::new(),
+        line: 0,
+        block: false,
+        started: false,
+        _t: PhantomData,
+    }
+}
+
+/// Like [`parse_lines_from`], but stops after the first blank line following a non-blank one.
+///
+/// Pass `&mut reader` to continue reading the next block afterward.
+/// Line numbers in errors are relative to the start of the block.
+pub fn parse_block_from<T: FromStr, R: BufRead>(reader: R) -> ReadLines<T, R> {
+    ReadLines {
+        block: true,
+        ..parse_lines_from(reader)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseVecError<T> {
-    #[error("missing element")]
-    MissingElement,
-    #[error("too many elements")]
-    TooManyElements,
-    #[error("parse error")]
-    ParseError(#[from] T),
+    #[error("missing element {index} at position {pos}")]
+    MissingElement { index: usize, pos: usize },
+    #[error("too many elements, first extra element at position {pos}")]
+    TooManyElements { pos: usize },
+    #[error("unbalanced bracket at position {pos}")]
+    UnbalancedBracket { pos: usize },
+    #[error("parse error at position {pos}")]
+    ParseError {
+        pos: usize,
+        #[source]
+        source: T,
+    },
+}
+
+/// Describes how a list is written down, e.g. `(1,2,3)`, `[a b c]` or `{(1,2);(3,4)}`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ListFormat<'a> {
+    /// matching pairs of brackets, one pair enclosing the whole list is stripped and elements are never split inside of a pair
+    pub brackets: &'a [(char, char)],
+    /// characters that separate elements
+    pub delimiters: &'a [char],
+    /// whether whitespace also separates elements
+    pub whitespace: bool,
+}
+
+impl ListFormat<'static> {
+    const BRACKETS: &'static [(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}')];
+
+    /// the format used by [`parse_vec`]: elements separated by `,`, `;`, `|` or whitespace
+    pub const DEFAULT: Self = Self {
+        brackets: Self::BRACKETS,
+        delimiters: &[',', ';', '|'],
+        whitespace: true,
+    };
+
+    /// elements separated only by `,`, whitespace is allowed around elements
+    pub const COMMA: Self = Self {
+        brackets: Self::BRACKETS,
+        delimiters: &[','],
+        whitespace: false,
+    };
 }
 
+impl ListFormat<'_> {
+    fn closing_bracket(&self, c: char) -> Option<char> {
+        self.brackets
+            .iter()
+            .find(|&&(open, _)| open == c)
+            .map(|&(_, close)| close)
+    }
+
+    fn is_closing_bracket(&self, c: char) -> bool {
+        self.brackets.iter().any(|&(_, close)| close == c)
+    }
+
+    fn is_delimiter(&self, c: char) -> bool {
+        self.delimiters.contains(&c) || (self.whitespace && c.is_whitespace())
+    }
+
+    /// Trims `s` and strips one pair of brackets if it encloses the whole list.
+    ///
+    /// Returns the inner part together with its offset in `s`.
+    fn strip_brackets<'s>(&self, s: &'s str) -> Result<(usize, &'s str), usize> {
+        let trimmed = s.trim();
+        let offset = trimmed.as_ptr() as usize - s.as_ptr() as usize;
+        let Some(first) = trimmed.chars().next() else {
+            return Ok((offset, trimmed));
+        };
+        if self.closing_bracket(first).is_none() {
+            return Ok((offset, trimmed));
+        }
+
+        let mut stack = vec![];
+        for (i, c) in trimmed.char_indices() {
+            if let Some(close) = self.closing_bracket(c) {
+                stack.push(close);
+            } else if self.is_closing_bracket(c) {
+                if stack.pop() != Some(c) {
+                    return Err(offset + i);
+                }
+                if stack.is_empty() {
+                    if i + c.len_utf8() == trimmed.len() {
+                        let inner = &trimmed[first.len_utf8()..i];
+                        return Ok((offset + first.len_utf8(), inner));
+                    }
+
+                    // the first bracket does not enclose the whole list
+                    break;
+                }
+            }
+        }
+
+        Ok((offset, trimmed))
+    }
+}
+
+/// Iterator over the trimmed, non-empty top-level elements of a list together with their offsets.
+struct ListElements<'s, 'f> {
+    s: &'s str,
+    offset: usize,
+    pos: usize,
+    format: &'f ListFormat<'f>,
+}
+
+impl<'s> Iterator for ListElements<'s, '_> {
+    type Item = Result<(usize, &'s str), usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.s.len() {
+            let start = self.pos;
+            let mut stack = vec![];
+            let mut end = self.s.len();
+            for (i, c) in self.s[start..].char_indices() {
+                let i = start + i;
+                if let Some(close) = self.format.closing_bracket(c) {
+                    stack.push(close);
+                } else if self.format.is_closing_bracket(c) {
+                    if stack.pop() != Some(c) {
+                        self.pos = self.s.len();
+                        return Some(Err(self.offset + i));
+                    }
+                } else if stack.is_empty() && self.format.is_delimiter(c) {
+                    end = i;
+                    break;
+                }
+            }
+            if !stack.is_empty() {
+                self.pos = self.s.len();
+                return Some(Err(self.offset + self.s.len()));
+            }
+
+            self.pos = self.s[end..]
+                .chars()
+                .next()
+                .map_or(end, |c| end + c.len_utf8());
+            let elem = self.s[start..end].trim_start();
+            let elem_start = end - elem.len();
+            let elem = elem.trim_end();
+            if !elem.is_empty() {
+                return Some(Ok((self.offset + elem_start, elem)));
+            }
+        }
+
+        None
+    }
+}
+
+fn list_elements<'s, 'f>(
+    s: &'s str,
+    format: &'f ListFormat<'f>,
+) -> Result<(usize, ListElements<'s, 'f>), usize> {
+    let (offset, inner) = format.strip_brackets(s)?;
+    Ok((
+        offset + inner.len(),
+        ListElements {
+            s: inner,
+            offset,
+            pos: 0,
+            format,
+        },
+    ))
+}
+
+/// Parses a list written in the given format, converting each element with `f`.
+///
+/// The number of elements has to be inside of `len`.
+/// Positions in errors are byte offsets into `s`.
+/// Nested lists can be parsed by calling another list parser from `f`, positions of inner errors are relative to the element.
+pub fn parse_list_with<T, E, B: FromIterator<T>>(
+    s: &str,
+    format: &ListFormat,
+    len: impl RangeBounds<usize>,
+    mut f: impl FnMut(&str) -> Result<T, E>,
+) -> Result<B, ParseVecError<E>> {
+    let (end, elements) =
+        list_elements(s, format).map_err(|pos| ParseVecError::UnbalancedBracket { pos })?;
+    let mut count = 0;
+    let result = elements
+        .map(|elem| {
+            let (pos, elem) = elem.map_err(|pos| ParseVecError::UnbalancedBracket { pos })?;
+            if !len.contains(&(count + 1)) && len.contains(&count) {
+                return Err(ParseVecError::TooManyElements { pos });
+            }
+            count += 1;
+            f(elem).map_err(|source| ParseVecError::ParseError { pos, source })
+        })
+        .collect::<Result<B, _>>()?;
+
+    if !len.contains(&count) {
+        return Err(ParseVecError::MissingElement {
+            index: count,
+            pos: end,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Parses a list written in the given format, see [`parse_list_with`].
+pub fn parse_list<T: FromStr, B: FromIterator<T>>(
+    s: &str,
+    format: &ListFormat,
+    len: impl RangeBounds<usize>,
+) -> Result<B, ParseVecError<<T as FromStr>::Err>> {
+    parse_list_with(s, format, len, str::parse)
+}
+
+/// Parses a vector of any length in the [`ListFormat::DEFAULT`] format.
+pub fn parse_dvec<T: Scalar + FromStr>(
+    s: &str,
+) -> Result<DVector<T>, ParseVecError<<T as FromStr>::Err>> {
+    Ok(DVector::from_vec(parse_list(s, &ListFormat::DEFAULT, ..)?))
+}
+
+/// Parses a vector of length `D` in the [`ListFormat::DEFAULT`] format.
 pub fn parse_vec<T: Scalar + FromStr, const D: usize>(
     s: &str,
 ) -> Result<SVector<T, D>, ParseVecError<<T as FromStr>::Err>> {
-    let mut it = s
-        .trim_matches(|c: char| {
-            matches!(c, '(' | ')' | '[' | ']' | '{' | '}' | '|') || c.is_whitespace()
-        })
-        .split(|c: char| matches!(c, ',' | ';' | '|') || c.is_whitespace())
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .map(str::parse);
+    let (end, mut it) = list_elements(s, &ListFormat::DEFAULT)
+        .map_err(|pos| ParseVecError::UnbalancedBracket { pos })?;
 
     let mut data: [Option<T>; D] = std::array::from_fn(|_| None);
-    for elem in data.iter_mut() {
-        *elem = Some(it.next().ok_or(ParseVecError::MissingElement)??);
+    for (index, elem) in data.iter_mut().enumerate() {
+        let (pos, e) = it
+            .next()
+            .ok_or(ParseVecError::MissingElement { index, pos: end })?
+            .map_err(|pos| ParseVecError::UnbalancedBracket { pos })?;
+        *elem = Some(
+            e.parse()
+                .map_err(|source| ParseVecError::ParseError { pos, source })?,
+        );
     }
 
-    if it.next().is_some() {
-        return Err(ParseVecError::TooManyElements);
+    match it.next() {
+        None => {}
+        Some(Ok((pos, _))) => return Err(ParseVecError::TooManyElements { pos }),
+        Some(Err(pos)) => return Err(ParseVecError::UnbalancedBracket { pos }),
     }
 
     Ok(SVector::from_iterator(data.into_iter().flatten()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_strict() {
+        assert_eq!(parse_split_strict(" 1, 2 ,3\n", ','), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            parse_split_strict::<u32, Vec<_>>("1,,3", ','),
+            Err(StrictParseError::EmptyField { pos: 2 })
+        );
+        assert_eq!(
+            parse_split_strict::<u32, Vec<_>>("1,2,", ','),
+            Err(StrictParseError::EmptyField { pos: 4 })
+        );
+        assert_eq!(
+            parse_split_strict::<u32, Vec<_>>("1,2\n 3", ','),
+            Err(StrictParseError::TrailingGarbage { pos: 5 })
+        );
+        assert!(matches!(
+            parse_split_strict::<u32, Vec<_>>("1,x", ','),
+            Err(StrictParseError::ParseError {
+                line: 1,
+                pos: 2,
+                ..
+            })
+        ));
+
+        assert_eq!(parse_lines_strict("1\n 2\n3\n\n"), Ok(vec![1, 2, 3]));
+        assert_eq!(parse_lines_strict::<u32, Vec<_>>(""), Ok(vec![]));
+        assert_eq!(
+            parse_lines_strict::<u32, Vec<_>>("1\n\n3"),
+            Err(StrictParseError::BlankLine { line: 2 })
+        );
+        assert!(matches!(
+            parse_lines_strict::<u32, Vec<_>>("1\n2\n 3x"),
+            Err(StrictParseError::ParseError {
+                line: 3,
+                pos: 5,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_lines_from() {
+        let mut reader = "\n1\n2\n\n3\nx\n".as_bytes();
+        let block: Vec<u32> = parse_block_from(&mut reader)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(block, [1, 2]);
+        let rest: Vec<_> = parse_lines_from::<u32, _>(reader).collect();
+        assert!(matches!(
+            rest[..],
+            [Ok(3), Err(ReadError::Parse { line: 2, .. })]
+        ));
+    }
+
+    #[test]
+    fn test_parse_vec() {
+        assert_eq!(parse_vec::<i64, 3>("1,-2, 3"), Ok(Vec3i::new(1, -2, 3)));
+        assert_eq!(parse_vec::<i64, 2>("[ 4 | 5 ]"), Ok(Vec2i::new(4, 5)));
+        assert_eq!(
+            parse_vec::<i64, 3>("(1,2)"),
+            Err(ParseVecError::MissingElement { index: 2, pos: 4 })
+        );
+        assert_eq!(
+            parse_vec::<i64, 2>("1,2,3"),
+            Err(ParseVecError::TooManyElements { pos: 4 })
+        );
+        assert!(matches!(
+            parse_vec::<i64, 2>("1,x"),
+            Err(ParseVecError::ParseError { pos: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let v: Vec<u8> = parse_list("(0,2, 3)", &ListFormat::COMMA, 1..).unwrap();
+        assert_eq!(v, [0, 2, 3]);
+        assert_eq!(
+            parse_list::<u8, Vec<_>>("{}", &ListFormat::COMMA, 1..),
+            Err(ParseVecError::MissingElement { index: 0, pos: 1 })
+        );
+        assert_eq!(
+            parse_list::<u8, Vec<_>>("{1,2,3}", &ListFormat::COMMA, ..=2),
+            Err(ParseVecError::TooManyElements { pos: 5 })
+        );
+        assert_eq!(
+            parse_list::<u8, Vec<_>>("(1,(2)", &ListFormat::COMMA, ..),
+            Err(ParseVecError::UnbalancedBracket { pos: 6 })
+        );
+        assert_eq!(
+            parse_dvec::<i64>("1 2 3 4").unwrap(),
+            DVector::from_vec(vec![1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_list() {
+        let v: Vec<Vec2i> =
+            parse_list_with("[(1,2) (3, 4)]", &ListFormat::DEFAULT, 2..=2, parse_vec).unwrap();
+        assert_eq!(v, [Vec2i::new(1, 2), Vec2i::new(3, 4)]);
+
+        let e =
+            parse_list_with::<Vec2i, _, Vec<_>>("(1,2);(3,x)", &ListFormat::DEFAULT, .., parse_vec);
+        assert!(matches!(
+            e,
+            Err(ParseVecError::ParseError {
+                pos: 6,
+                source: ParseVecError::ParseError { pos: 3, .. }
+            })
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+use nalgebra::{SVector, Scalar};
+use num::PrimInt;
+
+/// `max - min` for `min <= max`, computed without overflowing `T`.
+fn extent<T: PrimInt>(min: T, max: T) -> u128 {
+    match (min.to_i128(), max.to_i128()) {
+        (Some(min), Some(max)) => min.abs_diff(max),
+        // only `u128` values don't fit into an `i128`
+        _ => max.to_u128().unwrap() - min.to_u128().unwrap(),
+    }
+}
+
+/// An axis-aligned box with inclusive integer bounds, it is empty if `min > max` on any axis.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Aabb<T: Scalar, const D: usize> {
+    pub min: SVector<T, D>,
+    pub max: SVector<T, D>,
+}
+
+pub type Aabb2<T> = Aabb<T, 2>;
+pub type Aabb3<T> = Aabb<T, 3>;
+
+impl<T: Scalar + PrimInt, const D: usize> Aabb<T, D> {
+    pub fn new(min: SVector<T, D>, max: SVector<T, D>) -> Self {
+        Self { min, max }
+    }
+
+    /// The box spanned by any two opposite corners.
+    pub fn from_corners(a: SVector<T, D>, b: SVector<T, D>) -> Self {
+        Self::new(a.zip_map(&b, T::min), a.zip_map(&b, T::max))
+    }
+
+    /// The smallest box containing all points, `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = SVector<T, D>>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|p| Self::new(p, p))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.iter().zip(self.max.iter()).any(|(a, b)| a > b)
+    }
+
+    /// The side lengths, counting the integer positions along each axis.
+    ///
+    /// Panics if a side spans every `u128`, as its length doesn't fit.
+    pub fn size_inclusive(&self) -> SVector<u128, D> {
+        self.size_exclusive()
+            .map(|e| e.checked_add(1).expect("side length overflows u128"))
+    }
+
+    /// The side lengths, measured between the bounds.
+    pub fn size_exclusive(&self) -> SVector<u128, D> {
+        SVector::from_fn(|i, _| extent(self.min[i], self.max[i]))
+    }
+
+    /// The number of contained integer positions, `None` if it doesn't fit into a `u128`.
+    pub fn volume_inclusive(&self) -> Option<u128> {
+        if self.is_empty() {
+            return Some(0);
+        }
+        self.size_exclusive()
+            .iter()
+            .try_fold(1u128, |a, &e| a.checked_mul(e.checked_add(1)?))
+    }
+
+    /// The volume measured between the bounds, `None` if it doesn't fit into a `u128`.
+    pub fn volume_exclusive(&self) -> Option<u128> {
+        if self.is_empty() {
+            return Some(0);
+        }
+        self.size_exclusive()
+            .iter()
+            .try_fold(1u128, |a, &e| a.checked_mul(e))
+    }
+
+    pub fn contains(&self, p: &SVector<T, D>) -> bool {
+        (0..D).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty() || (self.contains(&other.min) && self.contains(&other.max))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let i = Self::new(
+            self.min.zip_map(&other.min, T::max),
+            self.max.zip_map(&other.max, T::min),
+        );
+        (!i.is_empty()).then_some(i)
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return *other;
+        } else if other.is_empty() {
+            return *self;
+        }
+        Self::new(
+            self.min.zip_map(&other.min, T::min),
+            self.max.zip_map(&other.max, T::max),
+        )
+    }
+
+    /// Checks if the boxes share at least one position, including their boundaries.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Checks if the boxes overlap strictly on every axis, merely touching boundaries does not count.
+    ///
+    /// A degenerate box like a line segment overlaps if it passes strictly through the inside of the other box.
+    pub fn overlaps_interior(&self, other: &Self) -> bool {
+        (0..D).all(|i| self.min[i] < other.max[i] && other.min[i] < self.max[i])
+    }
+
+    /// Checks if the boxes share boundary positions, but do not overlap.
+    pub fn touches(&self, other: &Self) -> bool {
+        self.intersects(other) && !self.overlaps_interior(other)
+    }
+
+    /// All `2^D` corners, including duplicates for degenerate boxes.
+    pub fn corners(&self) -> impl Iterator<Item = SVector<T, D>> + '_ {
+        (0..1usize << D).map(|mask| {
+            SVector::from_fn(|i, _| {
+                if mask & (1 << i) == 0 {
+                    self.min[i]
+                } else {
+                    self.max[i]
+                }
+            })
+        })
+    }
+}
+
+impl<T: Scalar + PrimInt> Aabb<T, 2> {
+    /// See [`Aabb::volume_inclusive`].
+    pub fn area_inclusive(&self) -> Option<u128> {
+        self.volume_inclusive()
+    }
+
+    /// See [`Aabb::volume_exclusive`].
+    pub fn area_exclusive(&self) -> Option<u128> {
+        self.volume_exclusive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Vec2i, Vec3i};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_aabb2() {
+        let a = Aabb2::from_corners(Vec2i::new(11, 1), Vec2i::new(2, 5));
+        assert_eq!(a, Aabb2::new(Vec2i::new(2, 1), Vec2i::new(11, 5)));
+        assert_eq!(a.area_inclusive(), Some(50));
+        assert_eq!(a.area_exclusive(), Some(36));
+        assert!(a.contains(&Vec2i::new(2, 5)) && !a.contains(&Vec2i::new(1, 5)));
+
+        let b = Aabb2::from_corners(Vec2i::new(11, 5), Vec2i::new(20, 20));
+        assert!(a.touches(&b) && !a.overlaps_interior(&b));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Aabb2::new(Vec2i::new(11, 5), Vec2i::new(11, 5)))
+        );
+        assert_eq!(
+            a.union(&b),
+            Aabb2::new(Vec2i::new(2, 1), Vec2i::new(20, 20))
+        );
+
+        let edge = Aabb2::from_corners(Vec2i::new(5, 0), Vec2i::new(5, 3));
+        assert!(a.overlaps_interior(&edge));
+        let edge = Aabb2::from_corners(Vec2i::new(2, 0), Vec2i::new(2, 3));
+        assert!(a.touches(&edge));
+        assert_eq!(
+            a.corners().collect::<Vec<_>>(),
+            [
+                Vec2i::new(2, 1),
+                Vec2i::new(11, 1),
+                Vec2i::new(2, 5),
+                Vec2i::new(11, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_full_range() {
+        let a = Aabb2::new(Vec2i::new(i64::MIN, 0), Vec2i::new(i64::MAX, 9));
+        assert_eq!(a.size_exclusive(), SVector::from([u64::MAX as u128, 9]));
+        assert_eq!(a.area_inclusive(), Some((1 << 64) * 10));
+        let a = Aabb2::new(
+            Vec2i::new(i64::MIN, i64::MIN),
+            Vec2i::new(i64::MAX, i64::MAX),
+        );
+        assert_eq!(a.area_inclusive(), None);
+        assert_eq!(
+            a.area_exclusive(),
+            Some(u64::MAX as u128 * u64::MAX as u128)
+        );
+
+        let a = Aabb::new(SVector::from([1u128]), SVector::from([u128::MAX]));
+        assert_eq!(a.volume_inclusive(), Some(u128::MAX));
+        assert_eq!(
+            Aabb::new(SVector::from([0u128]), SVector::from([u128::MAX])).volume_inclusive(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_aabb3() {
+        let a = Aabb3::from_points([Vec3i::new(1, 2, 3), Vec3i::new(-1, 5, 0)]).unwrap();
+        assert_eq!(a, Aabb3::new(Vec3i::new(-1, 2, 0), Vec3i::new(1, 5, 3)));
+        assert_eq!(a.volume_inclusive(), Some(3 * 4 * 4));
+        assert_eq!(a.corners().count(), 8);
+        let empty = Aabb3::new(Vec3i::new(0, 0, 0), Vec3i::new(-1, 0, 0));
+        assert!(empty.is_empty());
+        assert_eq!(empty.volume_inclusive(), Some(0));
+        assert_eq!(a.union(&empty), a);
+        assert_eq!(a.intersection(&empty), None);
+    }
+}
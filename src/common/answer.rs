@@ -0,0 +1,170 @@
+use num::{BigInt, ToPrimitive};
+use serde::{Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// The canonical form of an answer that equality and hashing are based on, every number is held by
+/// the narrowest variant that fits it.
+#[derive(PartialEq, Hash)]
+enum Key<'a> {
+    Negative(i128),
+    NonNegative(u128),
+    Big(&'a BigInt),
+    Text(&'a str),
+}
+
+/// The answer of a solver, big enough for every puzzle.
+///
+/// Numeric answers compare by value, so `Unsigned(42)`, `Signed(42)` and `Big(42)` are all equal.
+/// Text only equals the exact same text, never a number.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(i128),
+    Unsigned(u128),
+    Big(BigInt),
+    Text(String),
+}
+
+impl Answer {
+    /// The numeric value, text is parsed as a decimal integer.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Self::Signed(n) => Some((*n).into()),
+            Self::Unsigned(n) => Some((*n).into()),
+            Self::Big(n) => Some(n.clone()),
+            Self::Text(s) => s.trim().parse().ok(),
+        }
+    }
+
+    fn key(&self) -> Key<'_> {
+        match self {
+            Self::Signed(n) => match u128::try_from(*n) {
+                Ok(n) => Key::NonNegative(n),
+                Err(_) => Key::Negative(*n),
+            },
+            Self::Unsigned(n) => Key::NonNegative(*n),
+            Self::Big(n) => match (n.to_u128(), n.to_i128()) {
+                (Some(n), _) => Key::NonNegative(n),
+                (None, Some(n)) => Key::Negative(n),
+                (None, None) => Key::Big(n),
+            },
+            Self::Text(s) => Key::Text(s),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Signed(n) => n.fmt(f),
+            Self::Unsigned(n) => n.fmt(f),
+            Self::Big(n) => n.fmt(f),
+            Self::Text(s) => s.fmt(f),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Numbers that don't fit into 128 bits are serialized as strings.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Signed(n) => serializer.serialize_i128(*n),
+            Self::Unsigned(n) => serializer.serialize_u128(*n),
+            Self::Big(n) => serializer.collect_str(n),
+            Self::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident, $wide:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Self::$variant(value as $wide)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(Signed, i128, i8, i16, i32, i64, i128, isize);
+impl_from!(Unsigned, u128, u8, u16, u32, u64, u128, usize);
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Self::Big(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display_and_eq() {
+        let big = BigInt::from(u128::MAX) * 1000u32;
+        assert_eq!(
+            Answer::from(big).to_string(),
+            "340282366920938463463374607431768211455000"
+        );
+        assert_eq!(Answer::from(-7i64).to_string(), "-7");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+
+        assert_eq!(Answer::from(42u8), Answer::from(42i64));
+        assert_eq!(Answer::from(42usize), Answer::from(BigInt::from(42)));
+        assert_eq!(Answer::from(-5i8), Answer::from(BigInt::from(-5)));
+        assert_ne!(Answer::from(42u64), Answer::from("42"));
+        assert_ne!(Answer::from("42"), Answer::from(" 42 "));
+        assert_ne!(Answer::from(-1i32), Answer::from(u128::MAX));
+        assert_ne!(Answer::from("abc"), Answer::from("abd"));
+        assert_ne!(Answer::from("abc"), Answer::from(0u8));
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(Answer::from(5u32));
+        assert!(set.contains(&Answer::from(5i128)));
+        assert!(set.contains(&Answer::from(BigInt::from(5))));
+        assert!(!set.contains(&Answer::from("5")));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let answers = [
+            Answer::from(-3i32),
+            Answer::from(u128::MAX),
+            Answer::from(BigInt::from(u128::MAX) + 1),
+            Answer::from("text"),
+        ];
+        assert_eq!(
+            serde_json::to_string(&answers).unwrap(),
+            r#"[-3,340282366920938463463374607431768211455,"340282366920938463463374607431768211456","text"]"#
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::common::gf2_words;
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign};
+use std::str::FromStr;
+use thiserror::Error;
+use tinyvec::TinyVec;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Error)]
+pub enum ParseBitSetError {
+    #[error("missing '[' or ']'")]
+    MissingBrackets,
+    #[error("invalid character {c:?} at index {index}, expected '.' or '#'")]
+    InvalidChar { index: usize, c: char },
+}
+
+/// A fixed number of bits, stored inline for up to 128 bits and on the heap beyond.
+///
+/// Bit `i` is at `words[i / 64] >> (i % 64)`, the same layout as the GF(2) helpers use.
+/// Bits past the length are always zero, so equal sets have equal words.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    len: usize,
+    words: TinyVec<[u64; 2]>,
+}
+
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        let mut words = TinyVec::new();
+        words.resize(gf2_words(len), 0);
+        Self { len, words }
+    }
+
+    pub fn from_fn(len: usize, mut f: impl FnMut(usize) -> bool) -> Self {
+        let mut set = Self::new(len);
+        for i in (0..len).filter(|&i| f(i)) {
+            set.flip(i);
+        }
+        set
+    }
+
+    /// Builds a set from packed words, bits past `len` are ignored.
+    pub fn from_words(len: usize, words: &[u64]) -> Self {
+        let mut set = Self::new(len);
+        set.words
+            .iter_mut()
+            .zip(words)
+            .for_each(|(dst, &src)| *dst = src);
+        set.clear_excess();
+        set
+    }
+
+    fn clear_excess(&mut self) {
+        let used = self.len % u64::BITS as usize;
+        if used != 0 {
+            let last = self.words.len() - 1;
+            self.words[last] &= (1 << used) - 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Changes the length, new bits are unset.
+    pub fn resize(&mut self, len: usize) {
+        self.len = len;
+        self.words.resize(gf2_words(len), 0);
+        self.clear_excess();
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "bit {index} out of range");
+        (self.words[index / 64] >> (index % 64)) & 1 != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        if self.get(index) != value {
+            self.flip(index);
+        }
+    }
+
+    pub fn flip(&mut self, index: usize) {
+        assert!(index < self.len, "bit {index} out of range");
+        self.words[index / 64] ^= 1 << (index % 64);
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    /// The indices of all set bits in ascending order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let nonzero = |w: u64| Some(w).filter(|&w| w != 0);
+            std::iter::successors(nonzero(word), move |&w| nonzero(w & (w - 1)))
+                .map(move |w| i * 64 + w.trailing_zeros() as usize)
+        })
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        /// Panics if the lengths differ.
+        impl $op_assign<&BitSet> for BitSet {
+            fn $method_assign(&mut self, rhs: &BitSet) {
+                assert_eq!(self.len, rhs.len, "bit set lengths differ");
+                self.words
+                    .iter_mut()
+                    .zip(&rhs.words)
+                    .for_each(|(a, b)| a.$method_assign(b));
+            }
+        }
+
+        impl $op<&BitSet> for &BitSet {
+            type Output = BitSet;
+
+            fn $method(self, rhs: &BitSet) -> BitSet {
+                let mut result = self.clone();
+                result.$method_assign(rhs);
+                result
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+
+/// Parses the `[.#.#]` notation, where `#` is a set bit.
+impl FromStr for BitSet {
+    type Err = ParseBitSetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .ok_or(ParseBitSetError::MissingBrackets)?;
+        let mut set = Self::new(s.len());
+        for (index, c) in s.chars().enumerate() {
+            match c {
+                '.' => {}
+                '#' => set.flip(index),
+                _ => return Err(ParseBitSetError::InvalidChar { index, c }),
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl Display for BitSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for i in 0..self.len {
+            write!(f, "{}", if self.get(i) { '#' } else { '.' })?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        let set: BitSet = "[.##.#]".parse().unwrap();
+        assert_eq!(set.len(), 5);
+        assert_eq!(set.words(), [0b10110]);
+        assert_eq!(set.to_string(), "[.##.#]");
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), [1, 2, 4]);
+        assert_eq!("[]".parse(), Ok(BitSet::new(0)));
+
+        assert_eq!(
+            ".#".parse::<BitSet>(),
+            Err(ParseBitSetError::MissingBrackets)
+        );
+        assert_eq!(
+            "[.x]".parse::<BitSet>(),
+            Err(ParseBitSetError::InvalidChar { index: 1, c: 'x' })
+        );
+    }
+
+    #[test]
+    fn test_ops() {
+        let a = BitSet::from_fn(6, |i| i % 2 == 0);
+        let b = BitSet::from_fn(6, |i| i < 3);
+        assert_eq!((&a ^ &b).to_string(), "[.#..#.]");
+        assert_eq!((&a & &b).to_string(), "[#.#...]");
+        assert_eq!((&a | &b).to_string(), "[###.#.]");
+
+        let mut c = a.clone();
+        c ^= &a;
+        assert_eq!(c, BitSet::new(6));
+        c.set(5, true);
+        c.set(5, true);
+        assert_eq!(c.count_ones(), 1);
+    }
+
+    #[test]
+    fn test_wide() {
+        // 200 bits spill onto the heap
+        let mut set = BitSet::from_fn(200, |i| i % 50 == 49);
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), [49, 99, 149, 199]);
+        set.flip(130);
+        assert_eq!(set.count_ones(), 5);
+        assert!(set.get(130));
+
+        set.resize(100);
+        assert_eq!(set.count_ones(), 2);
+        set.resize(200);
+        assert_eq!(set.count_ones(), 2);
+        assert_eq!(BitSet::from_words(3, &[u64::MAX]).words(), [0b111]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_out_of_range() {
+        BitSet::new(40).flip(40);
+    }
+}
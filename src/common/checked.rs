@@ -0,0 +1,94 @@
+use num::{CheckedAdd, CheckedMul, One, Zero};
+use thiserror::Error;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Error)]
+#[error("arithmetic overflow in day {day} part {part}")]
+pub struct OverflowError {
+    pub day: u8,
+    pub part: u8,
+}
+
+/// Arithmetic for one puzzle part.
+///
+/// With the `checked` feature every operation is overflow-checked and reports an [`OverflowError`]
+/// naming the part, otherwise it is plain arithmetic that only panics in debug builds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Arith {
+    day: u8,
+    part: u8,
+}
+
+impl Arith {
+    pub const fn new(day: u8, part: u8) -> Self {
+        Self { day, part }
+    }
+
+    pub fn error(&self) -> OverflowError {
+        OverflowError {
+            day: self.day,
+            part: self.part,
+        }
+    }
+
+    pub fn add<T: CheckedAdd>(&self, a: T, b: T) -> Result<T, OverflowError> {
+        if cfg!(feature = "checked") {
+            a.checked_add(&b).ok_or(self.error())
+        } else {
+            Ok(a + b)
+        }
+    }
+
+    pub fn mul<T: CheckedMul>(&self, a: T, b: T) -> Result<T, OverflowError> {
+        if cfg!(feature = "checked") {
+            a.checked_mul(&b).ok_or(self.error())
+        } else {
+            Ok(a * b)
+        }
+    }
+
+    pub fn sum<T: CheckedAdd + Zero>(
+        &self,
+        values: impl IntoIterator<Item = T>,
+    ) -> Result<T, OverflowError> {
+        values
+            .into_iter()
+            .try_fold(T::zero(), |a, b| self.add(a, b))
+    }
+
+    pub fn product<T: CheckedMul + One>(
+        &self,
+        values: impl IntoIterator<Item = T>,
+    ) -> Result<T, OverflowError> {
+        values.into_iter().try_fold(T::one(), |a, b| self.mul(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_arith() {
+        let arith = Arith::new(6, 2);
+        assert_eq!(arith.add(2u64, 3), Ok(5));
+        assert_eq!(arith.mul(2u8, 100), Ok(200));
+        assert_eq!(arith.sum([1u32, 2, 3]), Ok(6));
+        assert_eq!(arith.product([2i64, -3, 4]), Ok(-24));
+        assert_eq!(
+            arith.error().to_string(),
+            "arithmetic overflow in day 6 part 2"
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let arith = Arith::new(7, 1);
+        let error = OverflowError { day: 7, part: 1 };
+        assert_eq!(arith.add(u64::MAX, 1), Err(error));
+        assert_eq!(arith.mul(1u64 << 32, 1 << 32), Err(error));
+        assert_eq!(arith.sum([i8::MIN, -1]), Err(error));
+        assert_eq!(arith.product([16u8; 2]), Err(error));
+    }
+}
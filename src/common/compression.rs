@@ -0,0 +1,306 @@
+use crate::common::{Aabb2, Grid, Interval, RectilinearPolygon, Vec2i};
+use itertools::Itertools;
+
+/// Maps sorted distinct coordinates to dense cell indices and back.
+///
+/// Without gaps every coordinate is its own cell.
+/// With gaps the values between two neighboring coordinates form an additional cell,
+/// so the cells cover the whole span without holes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CoordinateCompression {
+    cells: Vec<Interval<i64>>,
+}
+
+impl CoordinateCompression {
+    pub fn new(coords: impl IntoIterator<Item = i64>) -> Self {
+        Self {
+            cells: coords
+                .into_iter()
+                .sorted_unstable()
+                .dedup()
+                .map(|c| Interval::new(c, c))
+                .collect(),
+        }
+    }
+
+    pub fn with_gaps(coords: impl IntoIterator<Item = i64>) -> Self {
+        let mut cells: Vec<Interval<i64>> = vec![];
+        for c in coords.into_iter().sorted_unstable().dedup() {
+            if let Some(last) = cells.last()
+                && last.end + 1 < c
+            {
+                cells.push(Interval::new(last.end + 1, c - 1));
+            }
+            cells.push(Interval::new(c, c));
+        }
+        Self { cells }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The index of the cell containing `coord`.
+    pub fn index(&self, coord: i64) -> Option<usize> {
+        let idx = self.cells.partition_point(|cell| cell.end < coord);
+        self.cells
+            .get(idx)
+            .filter(|cell| cell.contains(coord))
+            .map(|_| idx)
+    }
+
+    /// The real coordinates covered by the cell at `index`.
+    pub fn cell(&self, index: usize) -> Interval<i64> {
+        self.cells[index]
+    }
+
+    pub fn width(&self, index: usize) -> u64 {
+        self.cells[index].len() as u64
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Interval<i64>> + '_ {
+        self.cells.iter().copied()
+    }
+}
+
+/// A [`Grid`] over compressed coordinates, where every cell stands for a whole rectangle of real positions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompressedGrid<T> {
+    pub xs: CoordinateCompression,
+    pub ys: CoordinateCompression,
+    pub grid: Grid<T>,
+}
+
+impl<T> CompressedGrid<T> {
+    /// Creates a grid over the given compressions, filling every cell from its real bounds.
+    pub fn from_fn(
+        xs: CoordinateCompression,
+        ys: CoordinateCompression,
+        mut f: impl FnMut(Interval<i64>, Interval<i64>) -> T,
+    ) -> Self {
+        let mut data = Vec::with_capacity(xs.len() * ys.len());
+        for y in ys.cells() {
+            data.extend(xs.cells().map(|x| f(x, y)));
+        }
+        let grid = Grid::from_vec(xs.len(), ys.len(), data);
+        Self { xs, ys, grid }
+    }
+
+    /// The compressed position of the cell containing the real position `p`.
+    pub fn compress(&self, p: Vec2i) -> Option<Vec2i> {
+        Some(Vec2i::new(
+            self.xs.index(p.x)? as i64,
+            self.ys.index(p.y)? as i64,
+        ))
+    }
+
+    /// The real width and height of the cell at the compressed position `pos`.
+    pub fn cell_size(&self, pos: Vec2i) -> (u64, u64) {
+        (self.xs.width(pos.x as usize), self.ys.width(pos.y as usize))
+    }
+
+    pub fn cell_area(&self, pos: Vec2i) -> u64 {
+        let (w, h) = self.cell_size(pos);
+        w * h
+    }
+}
+
+impl CompressedGrid<bool> {
+    /// Marks all cells inside of or on the boundary of the polygon.
+    ///
+    /// Sweeps the rows with the same ray casting rule as [`RectilinearPolygon::classify`],
+    /// in `O(rows * (edges + columns))`.
+    pub fn rasterize_polygon(polygon: &RectilinearPolygon) -> Self {
+        let xs = CoordinateCompression::with_gaps(polygon.vertices().iter().map(|v| v.x));
+        let ys = CoordinateCompression::with_gaps(polygon.vertices().iter().map(|v| v.y));
+        let (width, height) = (xs.len(), ys.len());
+        let index = |c: &CoordinateCompression, v| c.index(v).unwrap();
+
+        let vertical: Vec<_> = polygon
+            .edges()
+            .filter(|(a, b)| a.x == b.x)
+            .map(|(a, b)| (index(&xs, a.x), a.y.min(b.y), a.y.max(b.y)))
+            .collect();
+        let mut data = Vec::with_capacity(width * height);
+        let mut crossings = vec![false; width];
+        // cells never contain a vertex coordinate in their inside, so one point decides the whole cell
+        for y in ys.cells().map(|cell| cell.start) {
+            crossings.fill(false);
+            for &(x, y0, y1) in &vertical {
+                if y0 <= y && y < y1 {
+                    crossings[x] = !crossings[x];
+                }
+            }
+
+            // a ray in positive x direction crosses all edges right of the cell
+            let row = data.len();
+            data.resize(row + width, false);
+            let mut inside = false;
+            for x in (0..width).rev() {
+                data[row + x] = inside;
+                inside ^= crossings[x];
+            }
+        }
+
+        for (a, b) in polygon.edges() {
+            let (x0, x1) = (index(&xs, a.x.min(b.x)), index(&xs, a.x.max(b.x)));
+            let (y0, y1) = (index(&ys, a.y.min(b.y)), index(&ys, a.y.max(b.y)));
+            for y in y0..=y1 {
+                data[y * width + x0..=y * width + x1].fill(true);
+            }
+        }
+
+        let grid = Grid::from_vec(width, height, data);
+        Self { xs, ys, grid }
+    }
+
+    /// Marks all cells covered by at least one of the rectangles given by two opposite corners.
+    ///
+    /// Uses a 2D difference array, in `O(rects + cells)`.
+    pub fn rasterize_rects(rects: &[(Vec2i, Vec2i)]) -> Self {
+        let corners = || rects.iter().flat_map(|&(a, b)| [a, b]);
+        let xs = CoordinateCompression::with_gaps(corners().map(|c| c.x));
+        let ys = CoordinateCompression::with_gaps(corners().map(|c| c.y));
+        let (width, height) = (xs.len(), ys.len());
+
+        // coverage[(x, y)] is the sum of all differences in [0, x] × [0, y]
+        let mut coverage = vec![0i32; (width + 1) * (height + 1)];
+        for &(a, b) in rects {
+            let rect = Aabb2::from_corners(a, b);
+            let (x0, x1) = (
+                xs.index(rect.min.x).unwrap(),
+                xs.index(rect.max.x).unwrap() + 1,
+            );
+            let (y0, y1) = (
+                ys.index(rect.min.y).unwrap(),
+                ys.index(rect.max.y).unwrap() + 1,
+            );
+            let stride = width + 1;
+            coverage[y0 * stride + x0] += 1;
+            coverage[y0 * stride + x1] -= 1;
+            coverage[y1 * stride + x0] -= 1;
+            coverage[y1 * stride + x1] += 1;
+        }
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * (width + 1) + x;
+                if x > 0 {
+                    coverage[i] += coverage[i - 1];
+                }
+                if y > 0 {
+                    coverage[i] += coverage[i - width - 1];
+                }
+                if x > 0 && y > 0 {
+                    coverage[i] -= coverage[i - width - 2];
+                }
+            }
+        }
+
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| coverage[y * (width + 1) + x] > 0)
+            .collect();
+        let grid = Grid::from_vec(width, height, data);
+        Self { xs, ys, grid }
+    }
+
+    /// The total real area of all marked cells.
+    pub fn area(&self) -> u64 {
+        self.grid
+            .pos_iter()
+            .filter(|(_, marked)| **marked)
+            .map(|(pos, _)| self.cell_area(pos))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Containment;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_compression() {
+        let c = CoordinateCompression::new([30, 10, 20, 10]);
+        assert_eq!(c.len(), 3);
+        assert_eq!(c.index(20), Some(1));
+        assert_eq!(c.index(25), None);
+
+        let c = CoordinateCompression::with_gaps([30, 10, 11, 20]);
+        assert_eq!(
+            c.cells().collect::<Vec<_>>(),
+            [
+                Interval::new(10, 10),
+                Interval::new(11, 11),
+                Interval::new(12, 19),
+                Interval::new(20, 20),
+                Interval::new(21, 29),
+                Interval::new(30, 30),
+            ]
+        );
+        assert_eq!(c.index(25), Some(4));
+        assert_eq!(c.width(2), 8);
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let polygon = RectilinearPolygon::new(vec![
+            Vec2i::new(0, 0),
+            Vec2i::new(20, 0),
+            Vec2i::new(20, 40),
+            Vec2i::new(40, 40),
+            Vec2i::new(40, 0),
+            Vec2i::new(60, 0),
+            Vec2i::new(60, 60),
+            Vec2i::new(0, 60),
+        ])
+        .unwrap();
+        let g = CompressedGrid::rasterize_polygon(&polygon);
+        assert_eq!((g.grid.size_x, g.grid.size_y), (7, 5));
+        // all lattice points inside or on the boundary
+        assert_eq!(g.area(), 61 * 61 - 19 * 40);
+        assert!(!g.grid[g.compress(Vec2i::new(30, 10)).unwrap()]);
+
+        // a spiral with edges touching in every row
+        let spiral = RectilinearPolygon::new(
+            [
+                (0, 0),
+                (10, 0),
+                (10, 10),
+                (2, 10),
+                (2, 4),
+                (6, 4),
+                (6, 6),
+                (4, 6),
+                (4, 8),
+                (8, 8),
+                (8, 2),
+                (0, 2),
+            ]
+            .map(|(x, y)| Vec2i::new(x, y))
+            .to_vec(),
+        )
+        .unwrap();
+        let g = CompressedGrid::rasterize_polygon(&spiral);
+        for (pos, &marked) in g.grid.pos_iter() {
+            let p = Vec2i::new(
+                g.xs.cell(pos.x as usize).start,
+                g.ys.cell(pos.y as usize).start,
+            );
+            assert_eq!(marked, spiral.classify(p) != Containment::Outside, "{p:?}");
+        }
+
+        let g = CompressedGrid::rasterize_rects(&[
+            (Vec2i::new(0, 0), Vec2i::new(9, 9)),
+            (Vec2i::new(5, 5), Vec2i::new(14, 14)),
+        ]);
+        assert_eq!(g.area(), 100 + 100 - 25);
+        assert!(!g.grid[g.compress(Vec2i::new(12, 2)).unwrap()]);
+        assert!(g.grid[g.compress(Vec2i::new(14, 5)).unwrap()]);
+    }
+}
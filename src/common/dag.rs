@@ -0,0 +1,461 @@
+use crate::common::{Digraph, Reversed, Subgraph, csr};
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// Read access to a directed graph with dense `u32` node ids, which may only contain some of them.
+pub trait GraphView {
+    /// An upper bound for all node ids.
+    fn node_bound(&self) -> usize;
+
+    fn contains(&self, node: u32) -> bool;
+
+    fn successors(&self, node: u32) -> impl Iterator<Item = u32>;
+
+    fn predecessors(&self, node: u32) -> impl Iterator<Item = u32>;
+
+    fn nodes(&self) -> impl Iterator<Item = u32> {
+        (0..self.node_bound() as u32).filter(|&node| self.contains(node))
+    }
+}
+
+impl GraphView for Digraph {
+    fn node_bound(&self) -> usize {
+        self.node_count()
+    }
+
+    fn contains(&self, node: u32) -> bool {
+        (node as usize) < self.node_count()
+    }
+
+    fn successors(&self, node: u32) -> impl Iterator<Item = u32> {
+        Digraph::successors(self, node).iter().copied()
+    }
+
+    fn predecessors(&self, node: u32) -> impl Iterator<Item = u32> {
+        Digraph::predecessors(self, node).iter().copied()
+    }
+}
+
+impl<N, E> GraphView for Subgraph<'_, N, E>
+where
+    N: Fn(u32) -> bool,
+    E: Fn(u32, u32) -> bool,
+{
+    fn node_bound(&self) -> usize {
+        self.graph().node_count()
+    }
+
+    fn contains(&self, node: u32) -> bool {
+        Subgraph::contains(self, node)
+    }
+
+    fn successors(&self, node: u32) -> impl Iterator<Item = u32> {
+        Subgraph::successors(self, node)
+    }
+
+    fn predecessors(&self, node: u32) -> impl Iterator<Item = u32> {
+        Subgraph::predecessors(self, node)
+    }
+}
+
+/// The nodes of a cycle, each one has an edge to the next and the last one to the first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+#[error("the graph contains a cycle of length {}", .0.len())]
+pub struct Cycle(pub Vec<u32>);
+
+/// All nodes ordered such that every edge points forward, or a cycle if there is no such order.
+pub fn topological_order(graph: &impl GraphView) -> Result<Vec<u32>, Cycle> {
+    let mut in_degree = vec![0usize; graph.node_bound()];
+    let mut remaining = 0;
+    for node in graph.nodes() {
+        in_degree[node as usize] = graph.predecessors(node).count();
+        remaining += 1;
+    }
+
+    let mut queue: VecDeque<_> = graph
+        .nodes()
+        .filter(|&node| in_degree[node as usize] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(remaining);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for next in graph.successors(node) {
+            in_degree[next as usize] -= 1;
+            if in_degree[next as usize] == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+    if order.len() == remaining {
+        return Ok(order);
+    }
+
+    // every node that is left has a predecessor that is left as well, so walking backwards has to repeat a node
+    let mut step = vec![usize::MAX; graph.node_bound()];
+    let mut path = vec![];
+    let mut node = graph
+        .nodes()
+        .find(|&node| in_degree[node as usize] > 0)
+        .unwrap();
+    while step[node as usize] == usize::MAX {
+        step[node as usize] = path.len();
+        path.push(node);
+        node = graph
+            .predecessors(node)
+            .find(|&prev| in_degree[prev as usize] > 0)
+            .unwrap();
+    }
+    let mut cycle = path.split_off(step[node as usize]);
+    cycle.reverse();
+    Err(Cycle(cycle))
+}
+
+/// The strongly connected components in topological order, using Tarjan's algorithm.
+pub fn strongly_connected_components(graph: &impl GraphView) -> Vec<Vec<u32>> {
+    const UNVISITED: u32 = u32::MAX;
+    let n = graph.node_bound();
+    let (mut index, mut low, mut on_stack) = (vec![UNVISITED; n], vec![0; n], vec![false; n]);
+    let (mut stack, mut components, mut next_index) = (vec![], vec![], 0);
+
+    for root in graph.nodes() {
+        if index[root as usize] != UNVISITED {
+            continue;
+        }
+
+        let mut calls = vec![];
+        let mut discovered = Some(root);
+        loop {
+            if let Some(node) = discovered.take() {
+                index[node as usize] = next_index;
+                low[node as usize] = next_index;
+                next_index += 1;
+                on_stack[node as usize] = true;
+                stack.push(node);
+                calls.push((node, graph.successors(node)));
+            }
+            let Some((node, successors)) = calls.last_mut() else {
+                break;
+            };
+            let node = *node as usize;
+            if let Some(next) = successors.next() {
+                if index[next as usize] == UNVISITED {
+                    discovered = Some(next);
+                } else if on_stack[next as usize] {
+                    low[node] = low[node].min(index[next as usize]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent as usize] = low[parent as usize].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let start = stack.iter().rposition(|&n| n as usize == node).unwrap();
+                let component = stack.split_off(start);
+                component.iter().for_each(|&n| on_stack[n as usize] = false);
+                components.push(component);
+            }
+        }
+    }
+
+    // Tarjan finds the components in reverse topological order
+    components.reverse();
+    components
+}
+
+/// The acyclic graph of strongly connected components, numbered in topological order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condensation {
+    components: Vec<Vec<u32>>,
+    component_of: Vec<u32>,
+    out_offsets: Vec<u32>,
+    out_edges: Vec<u32>,
+    in_offsets: Vec<u32>,
+    in_edges: Vec<u32>,
+}
+
+impl Condensation {
+    pub fn new(graph: &impl GraphView) -> Self {
+        let components = strongly_connected_components(graph);
+        let mut component_of = vec![u32::MAX; graph.node_bound()];
+        for (c, nodes) in components.iter().enumerate() {
+            nodes
+                .iter()
+                .for_each(|&n| component_of[n as usize] = c as u32);
+        }
+
+        let mut edges: Vec<_> = graph
+            .nodes()
+            .flat_map(|from| graph.successors(from).map(move |to| (from, to)))
+            .map(|(from, to)| (component_of[from as usize], component_of[to as usize]))
+            .filter(|(from, to)| from != to)
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        let (out_offsets, out_edges) = csr(components.len(), edges.iter().copied());
+        let mut reversed: Vec<_> = edges.into_iter().map(|(from, to)| (to, from)).collect();
+        reversed.sort_unstable();
+        let (in_offsets, in_edges) = csr(components.len(), reversed.into_iter());
+
+        Self {
+            components,
+            component_of,
+            out_offsets,
+            out_edges,
+            in_offsets,
+            in_edges,
+        }
+    }
+
+    pub fn components(&self) -> &[Vec<u32>] {
+        &self.components
+    }
+
+    /// The component of a node of the original graph, `None` if it was not part of it.
+    pub fn component_of(&self, node: u32) -> Option<u32> {
+        Some(self.component_of[node as usize]).filter(|&c| c != u32::MAX)
+    }
+}
+
+impl GraphView for Condensation {
+    fn node_bound(&self) -> usize {
+        self.components.len()
+    }
+
+    fn contains(&self, node: u32) -> bool {
+        (node as usize) < self.components.len()
+    }
+
+    fn successors(&self, node: u32) -> impl Iterator<Item = u32> {
+        let node = node as usize;
+        self.out_edges[self.out_offsets[node] as usize..self.out_offsets[node + 1] as usize]
+            .iter()
+            .copied()
+    }
+
+    fn predecessors(&self, node: u32) -> impl Iterator<Item = u32> {
+        let node = node as usize;
+        self.in_edges[self.in_offsets[node] as usize..self.in_offsets[node + 1] as usize]
+            .iter()
+            .copied()
+    }
+}
+
+/// Computes a value for every node in topological order from the values of its predecessors.
+///
+/// The result is indexed by node id, nodes outside of the graph view have no value.
+pub fn fold_dag<T>(
+    graph: &impl GraphView,
+    mut f: impl FnMut(u32, &mut dyn Iterator<Item = &T>) -> T,
+) -> Result<Vec<Option<T>>, Cycle> {
+    let order = topological_order(graph)?;
+    let mut values: Vec<Option<T>> = (0..graph.node_bound()).map(|_| None).collect();
+    for node in order {
+        let value = f(
+            node,
+            &mut graph
+                .predecessors(node)
+                .map(|prev| values[prev as usize].as_ref().unwrap()),
+        );
+        values[node as usize] = Some(value);
+    }
+    Ok(values)
+}
+
+/// Marks the nodes reachable from `from`, including itself.
+fn mark_reachable(graph: &impl GraphView, from: u32) -> Vec<bool> {
+    let mut marked = vec![false; graph.node_bound()];
+    if !graph.contains(from) {
+        return marked;
+    }
+    marked[from as usize] = true;
+    let mut stack = vec![from];
+    while let Some(node) = stack.pop() {
+        for n in graph.successors(node) {
+            if !marked[n as usize] {
+                marked[n as usize] = true;
+                stack.push(n);
+            }
+        }
+    }
+    marked
+}
+
+/// A view of the nodes on at least one path from `from` to `to`, with the edges between them.
+///
+/// Folding over it only orders these nodes, so cycles elsewhere in the graph don't matter.
+#[derive(Debug, Clone)]
+pub struct Between<'a, G> {
+    graph: &'a G,
+    on_path: Vec<bool>,
+}
+
+impl<'a, G: GraphView> Between<'a, G> {
+    pub fn new(graph: &'a G, from: u32, to: u32) -> Self {
+        let forward = mark_reachable(graph, from);
+        let backward = mark_reachable(&Reversed(graph), to);
+        let on_path = forward.iter().zip(backward).map(|(&f, b)| f && b).collect();
+        Self { graph, on_path }
+    }
+}
+
+impl<G: GraphView> GraphView for Between<'_, G> {
+    fn node_bound(&self) -> usize {
+        self.on_path.len()
+    }
+
+    fn contains(&self, node: u32) -> bool {
+        self.on_path[node as usize]
+    }
+
+    fn successors(&self, node: u32) -> impl Iterator<Item = u32> {
+        self.graph
+            .successors(node)
+            .filter(|&n| self.on_path[n as usize])
+    }
+
+    fn predecessors(&self, node: u32) -> impl Iterator<Item = u32> {
+        self.graph
+            .predecessors(node)
+            .filter(|&n| self.on_path[n as usize])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum CountPathsError {
+    /// A cycle on some path from the start to the end, which allows infinitely many paths.
+    #[error(transparent)]
+    Cycle(#[from] Cycle),
+    #[error("the number of paths does not fit into a usize")]
+    Overflow,
+}
+
+/// The number of distinct paths from `from` to `to`.
+pub fn count_paths(graph: &impl GraphView, from: u32, to: u32) -> Result<usize, CountPathsError> {
+    let counts = fold_dag(&Between::new(graph, from, to), |node, prev| {
+        prev.copied()
+            .try_fold((node == from) as usize, |count, c| count.checked_add(c?))
+    })?;
+    counts[to as usize]
+        .unwrap_or(Some(0))
+        .ok_or(CountPathsError::Overflow)
+}
+
+/// The number of edges on the longest path from `from` to `to`, `None` if there is no path.
+///
+/// Only fails for a cycle on some path from `from` to `to`, which allows arbitrarily long paths.
+pub fn longest_path(graph: &impl GraphView, from: u32, to: u32) -> Result<Option<usize>, Cycle> {
+    let lengths = fold_dag(&Between::new(graph, from, to), |node, prev| {
+        if node == from {
+            Some(0)
+        } else {
+            prev.filter_map(|&len| len).max().map(|len| len + 1)
+        }
+    })?;
+    Ok(lengths[to as usize].flatten())
+}
+
+/// The number of nodes reachable from `from`, including itself.
+pub fn reachable_count(graph: &impl GraphView, from: u32) -> usize {
+    mark_reachable(graph, from)
+        .into_iter()
+        .filter(|&r| r)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn diamond() -> Digraph {
+        "a: b c\nb: d\nc: d e\nd: f\ne: f".parse().unwrap()
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let g = diamond();
+        let order = topological_order(&g).unwrap();
+        let position = |n: u32| order.iter().position(|&o| o == n).unwrap();
+        assert!(g.edges().all(|(from, to)| position(from) < position(to)));
+
+        let g: Digraph = "a: b\nb: c\nc: d\nd: b e".parse().unwrap();
+        let Cycle(cycle) = topological_order(&g).unwrap_err();
+        let names: Vec<_> = cycle.iter().map(|&n| g.name(n)).collect();
+        assert_eq!(names.len(), 3);
+        for (i, &n) in cycle.iter().enumerate() {
+            assert!(g.successors(n).contains(&cycle[(i + 1) % cycle.len()]));
+        }
+
+        // removing a node of the cycle makes it acyclic again
+        let removed = [g.id("c").unwrap()];
+        assert_eq!(
+            topological_order(&g.without_nodes(&removed)).unwrap().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_condensation() {
+        let g: Digraph = "a: b\nb: c\nc: a d\nd: e\ne: d f".parse().unwrap();
+        let id = |name| g.id(name).unwrap();
+        let c = Condensation::new(&g);
+        let names = |component: &[u32]| {
+            let mut names: Vec<_> = component.iter().map(|&n| g.name(n)).collect();
+            names.sort_unstable();
+            names
+        };
+        assert_eq!(
+            c.components().iter().map(|c| names(c)).collect::<Vec<_>>(),
+            [vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]
+        );
+        assert_eq!(c.component_of(id("e")), Some(1));
+        assert_eq!(topological_order(&c), Ok(vec![0, 1, 2]));
+        assert_eq!(count_paths(&c, 0, 2), Ok(1));
+    }
+
+    #[test]
+    fn test_folds() {
+        let g = diamond();
+        let id = |name| g.id(name).unwrap();
+        assert_eq!(count_paths(&g, id("a"), id("f")), Ok(3));
+        assert_eq!(count_paths(&g, id("b"), id("e")), Ok(0));
+        assert_eq!(longest_path(&g, id("a"), id("f")), Ok(Some(3)));
+        assert_eq!(longest_path(&g, id("e"), id("a")), Ok(None));
+        assert_eq!(reachable_count(&g, id("c")), 4);
+
+        let g: Digraph = "a: b\nb: a".parse().unwrap();
+        assert!(count_paths(&g, 0, 1).is_err());
+
+        // cycles off every path from the start to the end don't matter
+        let g: Digraph = "a: b c\nb: d\nc: c\nd: e\ne: d\nf: a b".parse().unwrap();
+        let id = |name| g.id(name).unwrap();
+        assert_eq!(count_paths(&g, id("a"), id("b")), Ok(1));
+        assert_eq!(longest_path(&g, id("f"), id("b")), Ok(Some(2)));
+        assert_eq!(reachable_count(&g, id("a")), 5);
+        let Err(CountPathsError::Cycle(Cycle(cycle))) = count_paths(&g, id("a"), id("e")) else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(cycle.len(), 2);
+
+        // a chain of diamonds doubles the number of paths with every link
+        let chain = |links: usize| -> Digraph {
+            (0..links)
+                .map(|i| format!("n{i}: a{i} b{i}\na{i}: n{}\nb{i}: n{}\n", i + 1, i + 1))
+                .collect::<String>()
+                .parse()
+                .unwrap()
+        };
+        let g = chain(63);
+        assert_eq!(
+            count_paths(&g, g.id("n0").unwrap(), g.id("n63").unwrap()),
+            Ok(1 << 63)
+        );
+        let g = chain(64);
+        assert_eq!(
+            count_paths(&g, g.id("n0").unwrap(), g.id("n64").unwrap()),
+            Err(CountPathsError::Overflow)
+        );
+    }
+}
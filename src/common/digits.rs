@@ -0,0 +1,186 @@
+/// The number of digits of `n` in base `base`, zero has one digit.
+pub fn digit_count_base(n: u64, base: u64) -> u32 {
+    debug_assert!(base >= 2);
+    n.checked_ilog(base).map_or(1, |log| log + 1)
+}
+
+pub fn digit_count(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// The digits of a number, most significant first.
+#[derive(Debug, Clone)]
+pub struct Digits {
+    n: u64,
+    base: u64,
+    /// The place value of the next digit, zero when done.
+    place: u64,
+}
+
+impl Iterator for Digits {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.place == 0 {
+            return None;
+        }
+        let digit = self.n / self.place;
+        self.n %= self.place;
+        self.place /= self.base;
+        Some(digit)
+    }
+}
+
+pub fn digits_base(n: u64, base: u64) -> Digits {
+    Digits {
+        n,
+        base,
+        place: base.pow(digit_count_base(n, base) - 1),
+    }
+}
+
+pub fn digits(n: u64) -> Digits {
+    digits_base(n, 10)
+}
+
+/// The number with the given digits, most significant first.
+pub fn from_digits_base(digits: impl IntoIterator<Item = u64>, base: u64) -> u64 {
+    digits.into_iter().fold(0, |n, d| {
+        debug_assert!(d < base);
+        n * base + d
+    })
+}
+
+pub fn from_digits(digits: impl IntoIterator<Item = u64>) -> u64 {
+    from_digits_base(digits, 10)
+}
+
+/// Splits `n` into its leading digits and its last `low` digits.
+pub fn split_at_digit_base(n: u64, low: u32, base: u64) -> (u64, u64) {
+    match base.checked_pow(low) {
+        Some(place) => (n / place, n % place),
+        None => (0, n),
+    }
+}
+
+pub fn split_at_digit(n: u64, low: u32) -> (u64, u64) {
+    split_at_digit_base(n, low, 10)
+}
+
+/// `(base^(block * count) - 1) / (base^block - 1)`, the number that repeats a `block` digit number
+/// `count` times when multiplied with it, e.g. `123 * 1001001 = 123123123`.
+///
+/// `None` if it doesn't fit into a `u64`.
+pub fn repunit_base(block: u32, count: u32, base: u64) -> Option<u64> {
+    // repeating a number zero times leaves nothing
+    if count == 0 {
+        return Some(0);
+    }
+    let place = base.checked_pow(block)?;
+    (1..count).try_fold(1u64, |r, _| r.checked_mul(place)?.checked_add(1))
+}
+
+pub fn repunit(block: u32, count: u32) -> Option<u64> {
+    repunit_base(block, count, 10)
+}
+
+/// All divisors of `n` in ascending order, found by trial division.
+pub fn divisors(n: u64) -> Vec<u64> {
+    let (mut small, mut large) = (vec![], vec![]);
+    let mut d = 1;
+    while d <= n / d {
+        if n.is_multiple_of(d) {
+            small.push(d);
+            if d != n / d {
+                large.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+/// The Möbius function: 0 if `n` has a squared prime factor, otherwise -1 or 1 for an odd or even
+/// number of prime factors.
+pub fn mobius(mut n: u64) -> i8 {
+    assert!(
+        n > 0,
+        "the Möbius function is only defined for positive numbers"
+    );
+    let mut result = 1;
+    let mut p = 2;
+    while p <= n / p {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            result = -result;
+        }
+        p += 1;
+    }
+    if n > 1 { -result } else { result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(10), 2);
+        assert_eq!(digit_count(u64::MAX), 20);
+        assert_eq!(digit_count_base(0b1011, 2), 4);
+
+        assert_eq!(digits(9071).collect::<Vec<_>>(), [9, 0, 7, 1]);
+        assert_eq!(digits(0).collect::<Vec<_>>(), [0]);
+        assert_eq!(digits(u64::MAX).count(), 20);
+        assert_eq!(
+            digits_base(0xbeef, 16).collect::<Vec<_>>(),
+            [11, 14, 14, 15]
+        );
+        assert_eq!(from_digits(digits(1234500)), 1234500);
+        assert_eq!(from_digits_base([1, 0, 1, 1], 2), 11);
+
+        assert_eq!(split_at_digit(123456, 2), (1234, 56));
+        assert_eq!(split_at_digit(123456, 30), (0, 123456));
+        assert_eq!(split_at_digit_base(0b110101, 3, 2), (0b110, 0b101));
+    }
+
+    #[test]
+    fn test_repunit() {
+        assert_eq!(repunit(1, 4), Some(1111));
+        assert_eq!(repunit(3, 3), Some(1001001));
+        assert_eq!(repunit(5, 1), Some(1));
+        assert_eq!(repunit(5, 0), Some(0));
+        assert_eq!(repunit_base(30, 0, 10), Some(0));
+        assert_eq!(repunit_base(2, 3, 2), Some(0b10101));
+        assert_eq!(repunit(1, 20), Some(11111111111111111111));
+        assert_eq!(repunit(1, 21), None);
+    }
+
+    #[test]
+    fn test_number_theory() {
+        assert_eq!(divisors(1), [1]);
+        assert_eq!(divisors(36), [1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(13), [1, 13]);
+        assert_eq!(
+            (1..=10).map(mobius).collect::<Vec<_>>(),
+            [1, -1, -1, 0, -1, 1, -1, 0, 0, 1]
+        );
+        // the Möbius function sums to zero over the divisors of every n > 1
+        for n in 2..100 {
+            assert_eq!(
+                divisors(n)
+                    .into_iter()
+                    .map(|d| mobius(d) as i32)
+                    .sum::<i32>(),
+                0
+            );
+        }
+    }
+}
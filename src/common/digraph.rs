@@ -0,0 +1,296 @@
+use crate::common::{Interner, Symbols};
+use std::ops::Range;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Error)]
+pub enum ParseDigraphError {
+    #[error("missing ':' in line {line}")]
+    MissingColon { line: usize },
+    #[error("missing node name in line {line}")]
+    MissingName { line: usize },
+}
+
+/// A directed graph with interned node names.
+///
+/// Nodes are dense `u32` ids, the edges are stored contiguously per node in both directions.
+/// Parallel edges are merged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Digraph {
+    names: Symbols,
+    out_offsets: Vec<u32>,
+    out_edges: Vec<u32>,
+    in_offsets: Vec<u32>,
+    in_edges: Vec<u32>,
+}
+
+/// Groups the targets of `edges` by their source, `edges` must be sorted.
+pub(crate) fn csr(
+    node_count: usize,
+    edges: impl Iterator<Item = (u32, u32)>,
+) -> (Vec<u32>, Vec<u32>) {
+    let mut offsets = vec![0; node_count + 1];
+    let targets = edges
+        .map(|(from, to)| {
+            offsets[from as usize + 1] += 1;
+            to
+        })
+        .collect();
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+    (offsets, targets)
+}
+
+impl Digraph {
+    fn from_interned(names: &Interner, mut edges: Vec<(u32, u32)>) -> Self {
+        let n = names.len();
+        edges.sort_unstable();
+        edges.dedup();
+        let (out_offsets, out_edges) = csr(n, edges.iter().copied());
+
+        let mut reversed: Vec<_> = edges.into_iter().map(|(from, to)| (to, from)).collect();
+        reversed.sort_unstable();
+        let (in_offsets, in_edges) = csr(n, reversed.into_iter());
+
+        Self {
+            names: names.freeze(),
+            out_offsets,
+            out_edges,
+            in_offsets,
+            in_edges,
+        }
+    }
+
+    /// Builds the graph from named edges, nodes are numbered in order of their first appearance.
+    pub fn from_edges<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut interner = Interner::new();
+        let edges = edges
+            .into_iter()
+            .map(|(from, to)| (interner.intern(from), interner.intern(to)))
+            .collect();
+        Self::from_interned(&interner, edges)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.out_edges.len()
+    }
+
+    pub fn nodes(&self) -> Range<u32> {
+        0..self.node_count() as u32
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.names.get(name)
+    }
+
+    pub fn name(&self, node: u32) -> &str {
+        self.names.name(node)
+    }
+
+    pub fn names(&self) -> &Symbols {
+        &self.names
+    }
+
+    pub fn successors(&self, node: u32) -> &[u32] {
+        let node = node as usize;
+        &self.out_edges[self.out_offsets[node] as usize..self.out_offsets[node + 1] as usize]
+    }
+
+    pub fn predecessors(&self, node: u32) -> &[u32] {
+        let node = node as usize;
+        &self.in_edges[self.in_offsets[node] as usize..self.in_offsets[node + 1] as usize]
+    }
+
+    pub fn out_degree(&self, node: u32) -> usize {
+        self.successors(node).len()
+    }
+
+    pub fn in_degree(&self, node: u32) -> usize {
+        self.predecessors(node).len()
+    }
+
+    /// All edges as `(from, to)`, sorted.
+    pub fn edges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.nodes()
+            .flat_map(|from| self.successors(from).iter().map(move |&to| (from, to)))
+    }
+
+    /// The same graph with every edge pointing the other way.
+    pub fn reversed(&self) -> Self {
+        Self {
+            names: self.names.clone(),
+            out_offsets: self.in_offsets.clone(),
+            out_edges: self.in_edges.clone(),
+            in_offsets: self.out_offsets.clone(),
+            in_edges: self.out_edges.clone(),
+        }
+    }
+
+    /// A view of the nodes and edges accepted by both filters, an edge also needs both of its nodes.
+    pub fn subgraph<N, E>(&self, node_filter: N, edge_filter: E) -> Subgraph<'_, N, E>
+    where
+        N: Fn(u32) -> bool,
+        E: Fn(u32, u32) -> bool,
+    {
+        Subgraph {
+            graph: self,
+            node_filter,
+            edge_filter,
+        }
+    }
+
+    /// A view without the given nodes.
+    pub fn without_nodes<'a>(
+        &'a self,
+        removed: &'a [u32],
+    ) -> Subgraph<'a, impl Fn(u32) -> bool + 'a, impl Fn(u32, u32) -> bool + 'a> {
+        self.subgraph(move |node| !removed.contains(&node), |_, _| true)
+    }
+}
+
+/// Parses lines like `aaa: bbb ccc`, listing the successors of a node.
+///
+/// Line numbers in errors start at 1, like in [`crate::common::StrictParseError`].
+impl FromStr for Digraph {
+    type Err = ParseDigraphError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut interner = Interner::new();
+        let mut edges = vec![];
+        for (idx, l) in s.trim_end().lines().enumerate() {
+            let line = idx + 1;
+            let (from, to) = l
+                .split_once(':')
+                .ok_or(ParseDigraphError::MissingColon { line })?;
+            let from = from.trim();
+            if from.is_empty() {
+                return Err(ParseDigraphError::MissingName { line });
+            }
+            let from = interner.intern(from);
+            edges.extend(to.split_whitespace().map(|to| (from, interner.intern(to))));
+        }
+        Ok(Self::from_interned(&interner, edges))
+    }
+}
+
+/// A filtered view of a [`Digraph`], nothing is copied.
+#[derive(Debug, Clone, Copy)]
+pub struct Subgraph<'a, N, E> {
+    graph: &'a Digraph,
+    node_filter: N,
+    edge_filter: E,
+}
+
+impl<'a, N, E> Subgraph<'a, N, E>
+where
+    N: Fn(u32) -> bool,
+    E: Fn(u32, u32) -> bool,
+{
+    pub fn graph(&self) -> &'a Digraph {
+        self.graph
+    }
+
+    pub fn contains(&self, node: u32) -> bool {
+        (self.node_filter)(node)
+    }
+
+    pub fn contains_edge(&self, from: u32, to: u32) -> bool {
+        self.contains(from) && self.contains(to) && (self.edge_filter)(from, to)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = u32> + '_ {
+        self.graph.nodes().filter(|&node| self.contains(node))
+    }
+
+    /// The successors of `node`, which must be part of the subgraph.
+    pub fn successors(&self, node: u32) -> impl Iterator<Item = u32> + '_ {
+        self.graph
+            .successors(node)
+            .iter()
+            .copied()
+            .filter(move |&to| self.contains_edge(node, to))
+    }
+
+    /// The predecessors of `node`, which must be part of the subgraph.
+    pub fn predecessors(&self, node: u32) -> impl Iterator<Item = u32> + '_ {
+        self.graph
+            .predecessors(node)
+            .iter()
+            .copied()
+            .filter(move |&from| self.contains_edge(from, node))
+    }
+
+    pub fn out_degree(&self, node: u32) -> usize {
+        self.successors(node).count()
+    }
+
+    pub fn in_degree(&self, node: u32) -> usize {
+        self.predecessors(node).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const INPUT: &str = r#"svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee"#;
+
+    #[test]
+    fn test_parse() {
+        let g: Digraph = INPUT.parse().unwrap();
+        assert_eq!(g.node_count(), 8);
+        assert_eq!(g.edge_count(), 8);
+        let id = |name| g.id(name).unwrap();
+        assert_eq!(g.successors(id("svr")), [id("aaa"), id("bbb")]);
+        assert_eq!(g.predecessors(id("ccc")), [id("fft"), id("tty")]);
+        assert_eq!((g.in_degree(id("ddd")), g.out_degree(id("ddd"))), (1, 0));
+        assert_eq!(g.name(id("tty")), "tty");
+
+        let r = g.reversed();
+        assert_eq!(r.successors(id("ccc")), [id("fft"), id("tty")]);
+        assert_eq!(r.edges().count(), 8);
+
+        assert_eq!(
+            "aaa bbb".parse::<Digraph>(),
+            Err(ParseDigraphError::MissingColon { line: 1 })
+        );
+        assert_eq!(
+            "aaa: bbb\n: ccc".parse::<Digraph>(),
+            Err(ParseDigraphError::MissingName { line: 2 })
+        );
+    }
+
+    #[test]
+    fn test_from_edges() {
+        let g = Digraph::from_edges([("a", "b"), ("b", "c"), ("a", "b"), ("c", "a")]);
+        assert_eq!(g.edges().collect::<Vec<_>>(), [(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(g, "a: b\nb: c\nc: a".parse().unwrap());
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let g: Digraph = INPUT.parse().unwrap();
+        let id = |name| g.id(name).unwrap();
+        let removed = [id("fft")];
+        let s = g.without_nodes(&removed);
+        assert_eq!(s.nodes().count(), 7);
+        assert_eq!(s.out_degree(id("aaa")), 0);
+        assert_eq!(s.predecessors(id("ccc")).collect::<Vec<_>>(), [id("tty")]);
+
+        let s = g.subgraph(|_| true, |from, _| from != id("ccc"));
+        assert_eq!(s.successors(id("ccc")).count(), 0);
+        assert_eq!(s.in_degree(id("ddd")), 0);
+        assert_eq!(s.in_degree(id("ccc")), 2);
+    }
+}
@@ -0,0 +1,124 @@
+/// Union-find over the elements `0..n` with path compression and union by size.
+///
+/// The members of every component are additionally linked in a cycle,
+/// so they can be iterated in time proportional to the component size.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    next: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            next: (0..n).collect(),
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the representative of the component containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+
+        root
+    }
+
+    /// Merges the components of `a` and `b`, returns `false` if they were already connected.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.next.swap(a, b);
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The size of the component containing `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// The number of distinct components.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|&x| self.parent[x] == x)
+    }
+
+    pub fn component_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.roots().map(|root| self.size[root])
+    }
+
+    /// All elements in the same component as `x`, starting with `x`.
+    pub fn members(&self, x: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(x), move |&y| Some(self.next[y]).filter(|&n| n != x))
+    }
+
+    /// The members of every component.
+    pub fn components(&self) -> impl Iterator<Item = impl Iterator<Item = usize> + '_> + '_ {
+        self.roots().map(|root| self.members(root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut ds = DisjointSet::new(6);
+        assert!(ds.union(0, 1));
+        assert!(ds.union(2, 3));
+        assert!(ds.union(1, 3));
+        assert!(!ds.union(0, 2));
+        assert!(ds.same(0, 3));
+        assert!(!ds.same(0, 4));
+        assert_eq!(ds.component_count(), 3);
+        assert_eq!(ds.size_of(2), 4);
+        assert_eq!(ds.component_sizes().sorted().collect_vec(), [1, 1, 4]);
+        assert_eq!(ds.members(3).sorted().collect_vec(), [0, 1, 2, 3]);
+        assert_eq!(
+            ds.components()
+                .map(|c| c.sorted().collect_vec())
+                .sorted()
+                .collect_vec(),
+            [vec![0, 1, 2, 3], vec![4], vec![5]]
+        );
+    }
+}
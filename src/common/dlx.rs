@@ -0,0 +1,423 @@
+use std::ops::ControlFlow;
+
+/// Why a search returned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SearchEnd {
+    /// All solutions were visited.
+    Exhausted,
+    /// The callback asked to stop.
+    Stopped,
+    /// The node budget ran out before the search was complete.
+    BudgetExceeded,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SearchStats {
+    pub end: SearchEnd,
+    /// The number of rows tried.
+    pub nodes: u64,
+    pub solutions: u64,
+}
+
+/// An exact cover problem solved with Knuth's Algorithm X on dancing links.
+///
+/// Primary columns must be covered exactly once, secondary columns at most once.
+/// Columns `0..primary` are primary and `primary..primary + secondary` are secondary.
+/// Solutions are reported as the ids of the chosen rows in ascending order.
+///
+/// A primary column can also be given a multiplicity, see [`Dlx::set_multiplicity`].
+///
+/// The links are restored after every search, so the same problem can be searched repeatedly.
+#[derive(Debug, Clone)]
+pub struct Dlx {
+    primary: usize,
+    columns: usize,
+    rows: usize,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The header node of every node's column.
+    column: Vec<usize>,
+    /// The row id of every node, unused for headers.
+    row: Vec<usize>,
+    /// The number of rows in every column, indexed by header node.
+    size: Vec<usize>,
+    /// The number of rows every column still needs, indexed by header node.
+    need: Vec<usize>,
+    node_budget: u64,
+}
+
+impl Dlx {
+    const ROOT: usize = 0;
+
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        let columns = primary + secondary;
+        let headers = columns + 1;
+        let mut dlx = Self {
+            primary,
+            columns,
+            rows: 0,
+            left: (0..headers).collect(),
+            right: (0..headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+            need: vec![1; headers],
+            node_budget: u64::MAX,
+        };
+        // only primary columns are linked into the root list, so only they have to be covered
+        for h in 1..=primary {
+            dlx.left[h] = h - 1;
+            dlx.right[h - 1] = h;
+        }
+        dlx.left[Self::ROOT] = primary;
+        dlx.right[primary] = Self::ROOT;
+        dlx
+    }
+
+    /// Limits every following search to trying at most `budget` rows.
+    pub fn with_node_budget(mut self, budget: u64) -> Self {
+        self.node_budget = budget;
+        self
+    }
+
+    /// Requires the primary `column` to be covered exactly `count` times instead of once.
+    ///
+    /// The rows of the column are interchangeable, so they are only chosen in ascending order
+    /// and every solution is found once instead of once per permutation.
+    pub fn set_multiplicity(&mut self, column: usize, count: usize) {
+        assert!(column < self.primary, "column {column} is not primary");
+        assert!(count > 0, "a primary column has to be covered");
+        self.need[column + 1] = count;
+    }
+
+    pub fn primary(&self) -> usize {
+        self.primary
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Adds a row covering the given distinct columns and returns its id.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        let id = self.rows;
+        self.rows += 1;
+
+        let first = self.left.len();
+        for (k, &c) in columns.iter().enumerate() {
+            assert!(c < self.columns, "column {c} out of range");
+            debug_assert!(!columns[..k].contains(&c), "duplicate column {c}");
+            let header = c + 1;
+            let node = first + k;
+            self.left.push(if k == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if k + 1 == columns.len() {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.down[self.up[header]] = node;
+            self.up[header] = node;
+            self.column.push(header);
+            self.row.push(id);
+            self.size[header] += 1;
+        }
+        id
+    }
+
+    fn cover(&mut self, header: usize) {
+        let (l, r) = (self.left[header], self.right[header]);
+        self.right[l] = r;
+        self.left[r] = l;
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (l, r) = (self.left[header], self.right[header]);
+        self.right[l] = header;
+        self.left[r] = header;
+    }
+
+    /// Removes the row of `node` from all of its columns.
+    fn hide(&mut self, node: usize) {
+        let mut j = node;
+        loop {
+            let (u, d) = (self.up[j], self.down[j]);
+            self.down[u] = d;
+            self.up[d] = u;
+            self.size[self.column[j]] -= 1;
+            j = self.right[j];
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    fn unhide(&mut self, node: usize) {
+        let mut j = self.left[node];
+        loop {
+            let (u, d) = (self.up[j], self.down[j]);
+            self.down[u] = j;
+            self.up[d] = j;
+            self.size[self.column[j]] += 1;
+            if j == node {
+                break;
+            }
+            j = self.left[j];
+        }
+    }
+
+    /// Calls `f` with every solution until it breaks, the search is exhausted or the node budget runs out.
+    pub fn search(&mut self, mut f: impl FnMut(&[usize]) -> ControlFlow<()>) -> SearchStats {
+        let mut stats = SearchStats {
+            end: SearchEnd::Exhausted,
+            nodes: 0,
+            solutions: 0,
+        };
+        if let ControlFlow::Break(end) = self.search_from(&mut vec![], &mut stats, &mut f) {
+            stats.end = end;
+        }
+        stats
+    }
+
+    fn search_from(
+        &mut self,
+        chosen: &mut Vec<usize>,
+        stats: &mut SearchStats,
+        f: &mut impl FnMut(&[usize]) -> ControlFlow<()>,
+    ) -> ControlFlow<SearchEnd> {
+        if self.right[Self::ROOT] == Self::ROOT {
+            stats.solutions += 1;
+            let mut solution = chosen.clone();
+            solution.sort_unstable();
+            return match f(&solution) {
+                ControlFlow::Continue(()) => ControlFlow::Continue(()),
+                ControlFlow::Break(()) => ControlFlow::Break(SearchEnd::Stopped),
+            };
+        }
+
+        // choose the primary column with the fewest choices for its next row
+        let choices = |dlx: &Self, c: usize| dlx.size[c] as isize - dlx.need[c] as isize;
+        let mut header = self.right[Self::ROOT];
+        let mut c = header;
+        while c != Self::ROOT {
+            if choices(self, c) < choices(self, header) {
+                header = c;
+            }
+            c = self.right[c];
+        }
+        if choices(self, header) < 0 {
+            return ControlFlow::Continue(());
+        }
+
+        // the last row of a column covers it, before that every tried row is hidden for the remaining search,
+        // so the rows of a column are chosen in ascending order
+        let last = self.need[header] == 1;
+        if last {
+            self.cover(header);
+        } else {
+            self.need[header] -= 1;
+        }
+        let mut result = ControlFlow::Continue(());
+        let mut hidden = vec![];
+        let mut r = self.down[header];
+        while r != header {
+            if stats.nodes >= self.node_budget {
+                result = ControlFlow::Break(SearchEnd::BudgetExceeded);
+                break;
+            }
+            stats.nodes += 1;
+
+            if !last {
+                self.hide(r);
+                hidden.push(r);
+            }
+            chosen.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+            result = self.search_from(chosen, stats, f);
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            chosen.pop();
+
+            if result.is_break() {
+                break;
+            }
+            r = if last {
+                self.down[r]
+            } else {
+                self.down[header]
+            };
+        }
+        for r in hidden.into_iter().rev() {
+            self.unhide(r);
+        }
+        if last {
+            self.uncover(header);
+        } else {
+            self.need[header] += 1;
+        }
+        result
+    }
+
+    /// The first solution found, `Err` with the search statistics if the node budget ran out before.
+    pub fn first_solution(&mut self) -> Result<Option<Vec<usize>>, SearchStats> {
+        let mut solution = None;
+        let stats = self.search(|s| {
+            solution = Some(s.to_vec());
+            ControlFlow::Break(())
+        });
+        match stats.end {
+            SearchEnd::BudgetExceeded => Err(stats),
+            _ => Ok(solution),
+        }
+    }
+
+    /// The number of solutions, `Err` with the search statistics if the node budget ran out before.
+    pub fn count_solutions(&mut self) -> Result<u64, SearchStats> {
+        let stats = self.search(|_| ControlFlow::Continue(()));
+        match stats.end {
+            SearchEnd::BudgetExceeded => Err(stats),
+            _ => Ok(stats.solutions),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_knuth_example() {
+        let mut dlx = Dlx::new(7, 0);
+        for row in [
+            &[2, 4, 5][..],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            dlx.add_row(row);
+        }
+        assert_eq!(dlx.first_solution(), Ok(Some(vec![0, 3, 4])));
+        assert_eq!(dlx.count_solutions(), Ok(1));
+    }
+
+    #[test]
+    fn test_secondary() {
+        // place two dominoes on a row of 3 cells: the cells are secondary, so one may stay empty
+        let mut dlx = Dlx::new(2, 3);
+        for piece in 0..2 {
+            for x in 0..2 {
+                dlx.add_row(&[piece, 2 + x, 3 + x]);
+            }
+        }
+        assert_eq!(dlx.count_solutions(), Ok(0));
+
+        let mut dlx = Dlx::new(1, 3);
+        dlx.add_row(&[0, 1, 2]);
+        dlx.add_row(&[0, 2, 3]);
+        assert_eq!(dlx.count_solutions(), Ok(2));
+    }
+
+    #[test]
+    fn test_multiplicity() {
+        // two identical dominoes on a row of 5 cells, ignoring their order
+        let mut dlx = Dlx::new(1, 5);
+        for x in 0..4 {
+            dlx.add_row(&[0, 1 + x, 2 + x]);
+        }
+        dlx.set_multiplicity(0, 2);
+        assert_eq!(dlx.count_solutions(), Ok(3));
+        assert_eq!(dlx.first_solution(), Ok(Some(vec![0, 2])));
+
+        dlx.set_multiplicity(0, 3);
+        assert_eq!(dlx.count_solutions(), Ok(0));
+
+        // a column with a multiplicity next to plain ones
+        let mut dlx = Dlx::new(2, 4);
+        for x in 0..4 {
+            dlx.add_row(&[0, 2 + x]);
+            dlx.add_row(&[1, 2 + x]);
+        }
+        dlx.set_multiplicity(0, 3);
+        assert_eq!(dlx.count_solutions(), Ok(4));
+    }
+
+    #[test]
+    fn test_budget_and_stop() {
+        // tile a 2x6 strip with dominoes, which has 13 tilings
+        let (w, h) = (6, 2);
+        let mut dlx = Dlx::new(w * h, 0);
+        for y in 0..h {
+            for x in 0..w {
+                if x + 1 < w {
+                    dlx.add_row(&[y * w + x, y * w + x + 1]);
+                }
+                if y + 1 < h {
+                    dlx.add_row(&[y * w + x, (y + 1) * w + x]);
+                }
+            }
+        }
+        assert_eq!(dlx.count_solutions(), Ok(13));
+
+        let mut seen = 0;
+        let stats = dlx.search(|_| {
+            seen += 1;
+            if seen == 5 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!((stats.end, stats.solutions), (SearchEnd::Stopped, 5));
+
+        let mut dlx = dlx.with_node_budget(3);
+        let stats = dlx.count_solutions().unwrap_err();
+        assert_eq!((stats.end, stats.nodes), (SearchEnd::BudgetExceeded, 3));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::common::GraphView;
+
+const NONE: u32 = u32::MAX;
+
+/// A view of a graph with every edge pointing the other way.
+#[derive(Debug, Clone, Copy)]
+pub struct Reversed<'a, G>(pub &'a G);
+
+impl<G: GraphView> GraphView for Reversed<'_, G> {
+    fn node_bound(&self) -> usize {
+        self.0.node_bound()
+    }
+
+    fn contains(&self, node: u32) -> bool {
+        self.0.contains(node)
+    }
+
+    fn successors(&self, node: u32) -> impl Iterator<Item = u32> {
+        self.0.predecessors(node)
+    }
+
+    fn predecessors(&self, node: u32) -> impl Iterator<Item = u32> {
+        self.0.successors(node)
+    }
+}
+
+/// The dominator tree of the nodes reachable from a root.
+///
+/// A node `a` dominates `b` if every path from the root to `b` passes through `a`.
+/// Built with the Lengauer–Tarjan algorithm using path compression, in `O(m log n)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DominatorTree {
+    root: u32,
+    /// The immediate dominator of every node, `NONE` for the root and unreachable nodes.
+    idom: Vec<u32>,
+    /// Pre- and post-order numbers in the dominator tree, for constant time ancestor queries.
+    enter: Vec<u32>,
+    exit: Vec<u32>,
+}
+
+impl DominatorTree {
+    pub fn new(graph: &impl GraphView, root: u32) -> Self {
+        let n = graph.node_bound();
+
+        // depth first search numbering, everything below works on these numbers
+        let mut number = vec![NONE; n];
+        let mut vertex = vec![];
+        let mut parent = vec![];
+        let mut stack = vec![(root, NONE)];
+        while let Some((node, from)) = stack.pop() {
+            if number[node as usize] != NONE {
+                continue;
+            }
+            number[node as usize] = vertex.len() as u32;
+            vertex.push(node);
+            parent.push(from);
+            let v = number[node as usize];
+            stack.extend(
+                graph
+                    .successors(node)
+                    .filter(|&next| number[next as usize] == NONE)
+                    .map(|next| (next, v)),
+            );
+        }
+
+        let count = vertex.len();
+        let mut semi: Vec<u32> = (0..count as u32).collect();
+        let mut label = semi.clone();
+        let mut ancestor = vec![NONE; count];
+        let mut idom = vec![NONE; count];
+        let mut bucket: Vec<Vec<u32>> = vec![vec![]; count];
+        let mut path = vec![];
+
+        // the vertex with the smallest semidominator on the compressed forest path to `v`
+        let mut eval = |v: u32, ancestor: &mut [u32], label: &mut [u32], semi: &[u32]| {
+            if ancestor[v as usize] == NONE {
+                return v;
+            }
+            let mut x = v;
+            while ancestor[ancestor[x as usize] as usize] != NONE {
+                path.push(x);
+                x = ancestor[x as usize];
+            }
+            while let Some(x) = path.pop() {
+                let a = ancestor[x as usize] as usize;
+                if semi[label[a] as usize] < semi[label[x as usize] as usize] {
+                    label[x as usize] = label[a];
+                }
+                ancestor[x as usize] = ancestor[a];
+            }
+            label[v as usize]
+        };
+
+        for w in (1..count as u32).rev() {
+            for pred in graph.predecessors(vertex[w as usize]) {
+                let v = number[pred as usize];
+                if v == NONE {
+                    continue;
+                }
+                let u = eval(v, &mut ancestor, &mut label, &semi);
+                semi[w as usize] = semi[w as usize].min(semi[u as usize]);
+            }
+            bucket[semi[w as usize] as usize].push(w);
+            let p = parent[w as usize];
+            ancestor[w as usize] = p;
+
+            for v in std::mem::take(&mut bucket[p as usize]) {
+                let u = eval(v, &mut ancestor, &mut label, &semi);
+                idom[v as usize] = if semi[u as usize] < semi[v as usize] {
+                    u
+                } else {
+                    p
+                };
+            }
+        }
+        for w in 1..count {
+            if idom[w] != semi[w] {
+                idom[w] = idom[idom[w] as usize];
+            }
+        }
+
+        let mut tree = Self {
+            root,
+            idom: vec![NONE; n],
+            enter: vec![NONE; n],
+            exit: vec![NONE; n],
+        };
+        for w in 1..count {
+            tree.idom[vertex[w] as usize] = vertex[idom[w] as usize];
+        }
+        tree.number_tree(&vertex, &idom);
+        tree
+    }
+
+    /// The post-dominator tree: `a` post-dominates `b` if every path from `b` to `exit` passes through `a`.
+    pub fn post_dominators(graph: &impl GraphView, exit: u32) -> Self {
+        Self::new(&Reversed(graph), exit)
+    }
+
+    fn number_tree(&mut self, vertex: &[u32], idom: &[u32]) {
+        let count = vertex.len();
+        let mut offsets = vec![0; count + 1];
+        for &d in &idom[1..] {
+            offsets[d as usize + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+        let mut children = vec![0; count.saturating_sub(1)];
+        let mut next = offsets.clone();
+        for (w, &d) in idom.iter().enumerate().skip(1) {
+            children[next[d as usize]] = w;
+            next[d as usize] += 1;
+        }
+
+        let mut time = 0;
+        let mut stack = vec![(0, false)];
+        while let Some((v, done)) = stack.pop() {
+            let node = vertex[v] as usize;
+            if done {
+                self.exit[node] = time;
+            } else {
+                self.enter[node] = time;
+                stack.push((v, true));
+                stack.extend(
+                    children[offsets[v]..offsets[v + 1]]
+                        .iter()
+                        .map(|&c| (c, false)),
+                );
+            }
+            time += 1;
+        }
+    }
+
+    pub fn root(&self) -> u32 {
+        self.root
+    }
+
+    /// Whether `node` is reachable from the root.
+    pub fn contains(&self, node: u32) -> bool {
+        self.enter[node as usize] != NONE
+    }
+
+    /// The immediate dominator, `None` for the root and unreachable nodes.
+    pub fn idom(&self, node: u32) -> Option<u32> {
+        Some(self.idom[node as usize]).filter(|&d| d != NONE)
+    }
+
+    /// Whether every path from the root to `b` passes through `a`, every reachable node dominates itself.
+    pub fn dominates(&self, a: u32, b: u32) -> bool {
+        let (a, b) = (a as usize, b as usize);
+        self.contains(b as u32) && self.enter[a] <= self.enter[b] && self.exit[b] <= self.exit[a]
+    }
+
+    /// The dominators of `node` from itself up to the root, empty if it is unreachable.
+    pub fn dominators(&self, node: u32) -> impl Iterator<Item = u32> + '_ {
+        let start = Some(node).filter(|&n| self.contains(n));
+        std::iter::successors(start, |&n| self.idom(n))
+    }
+}
+
+/// The nodes on every path from `from` to `to` in path order, including both ends.
+///
+/// Empty if there is no path.
+pub fn mandatory_nodes(graph: &impl GraphView, from: u32, to: u32) -> Vec<u32> {
+    let mut nodes: Vec<_> = DominatorTree::new(graph, from).dominators(to).collect();
+    nodes.reverse();
+    nodes
+}
+
+/// The nodes whose removal disconnects `to` from `from`, in path order.
+pub fn articulation_nodes(graph: &impl GraphView, from: u32, to: u32) -> Vec<u32> {
+    let mut nodes = mandatory_nodes(graph, from, to);
+    nodes.retain(|&n| n != from && n != to);
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Digraph;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_dominators() {
+        // the example from Lengauer and Tarjan's paper
+        let g: Digraph = "r: a b c\na: d\nb: a d e\nc: f g\nd: l\ne: h\nf: i\ng: i j\nh: e k\ni: k\nj: i\nk: i r\nl: h"
+            .parse()
+            .unwrap();
+        let id = |name| g.id(name).unwrap();
+        let tree = DominatorTree::new(&g, id("r"));
+        let idom = |name| tree.idom(id(name)).map(|d| g.name(d));
+        assert_eq!(idom("r"), None);
+        for name in ["a", "b", "c", "d", "e", "h", "i", "k"] {
+            assert_eq!(idom(name), Some("r"), "{name}");
+        }
+        assert_eq!(idom("f"), Some("c"));
+        assert_eq!(idom("g"), Some("c"));
+        assert_eq!(idom("j"), Some("g"));
+        assert_eq!(idom("l"), Some("d"));
+
+        assert!(tree.dominates(id("c"), id("j")));
+        assert!(tree.dominates(id("j"), id("j")));
+        assert!(!tree.dominates(id("g"), id("i")));
+        assert_eq!(
+            tree.dominators(id("j"))
+                .map(|n| g.name(n))
+                .collect::<Vec<_>>(),
+            ["j", "g", "c", "r"]
+        );
+    }
+
+    #[test]
+    fn test_post_dominators() {
+        let g: Digraph = "a: b c\nb: d\nc: d\nd: e f\ne: g\nf: g\nh: g"
+            .parse()
+            .unwrap();
+        let id = |name| g.id(name).unwrap();
+        let tree = DominatorTree::post_dominators(&g, id("g"));
+        assert_eq!(tree.idom(id("a")), Some(id("d")));
+        assert_eq!(tree.idom(id("h")), Some(id("g")));
+
+        let names = |nodes: Vec<u32>| nodes.into_iter().map(|n| g.name(n)).collect::<Vec<_>>();
+        assert_eq!(
+            names(mandatory_nodes(&g, id("a"), id("g"))),
+            ["a", "d", "g"]
+        );
+        assert_eq!(names(articulation_nodes(&g, id("a"), id("g"))), ["d"]);
+        assert_eq!(mandatory_nodes(&g, id("h"), id("a")), Vec::<u32>::new());
+    }
+}
@@ -0,0 +1,624 @@
+use crate::common::{Aabb2, Mat2i, Mat2r128, Orientation, Rational128, Vec2i, Vec2r128};
+use nalgebra::{Matrix2, Scalar, Vector2};
+use num::{
+    BigInt, BigRational, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, ToPrimitive, Zero,
+};
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// How two closed segments intersect, with exact rational coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SegmentIntersection<P = Vec2r128> {
+    Disjoint,
+    Point(P),
+    /// The segments are collinear and share the segment between both points.
+    Overlap(P, P),
+}
+
+/// How two infinite lines intersect, with exact rational coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LineIntersection<P = Vec2r128> {
+    Parallel,
+    Coincident,
+    Point(P),
+}
+
+/// An exact result that is not representable as a [`Vec2r128`].
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[error("the exact result does not fit into 128 bit rationals")]
+pub struct Unrepresentable;
+
+/// Exact number types the rational algorithms work with, where every operation reports overflow.
+trait Exact: Scalar + Zero + One + Ord + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv {}
+
+impl<T: Scalar + Zero + One + Ord + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv> Exact for T {}
+
+pub fn to_rational(v: Vec2i) -> Vec2r128 {
+    v.map(|x| Rational128::from_integer(x as i128))
+}
+
+fn to_big_rational(v: Vec2i) -> Vector2<BigRational> {
+    v.map(|x| BigRational::from_integer(x.into()))
+}
+
+fn widen_scalar(x: Rational128) -> BigRational {
+    BigRational::new_raw((*x.numer()).into(), (*x.denom()).into())
+}
+
+fn widen(v: &Vec2r128) -> Vector2<BigRational> {
+    v.map(widen_scalar)
+}
+
+/// Converts an exact result back into 128 bits, `None` if it doesn't fit.
+///
+/// Intersections of integer points lie between the inputs, but their denominators can need up to
+/// 129 bits.
+fn from_big_rational(v: &Vector2<BigRational>) -> Option<Vec2r128> {
+    let x = Rational128::new_raw(v.x.numer().to_i128()?, v.x.denom().to_i128()?);
+    let y = Rational128::new_raw(v.y.numer().to_i128()?, v.y.denom().to_i128()?);
+    Some(Vec2r128::new(x, y))
+}
+
+fn from_big_segment(
+    i: SegmentIntersection<Vector2<BigRational>>,
+) -> Result<SegmentIntersection, Unrepresentable> {
+    let narrow = |p| from_big_rational(&p).ok_or(Unrepresentable);
+    Ok(match i {
+        SegmentIntersection::Disjoint => SegmentIntersection::Disjoint,
+        SegmentIntersection::Point(p) => SegmentIntersection::Point(narrow(p)?),
+        SegmentIntersection::Overlap(a, b) => SegmentIntersection::Overlap(narrow(a)?, narrow(b)?),
+    })
+}
+
+fn from_big_line(
+    i: LineIntersection<Vector2<BigRational>>,
+) -> Result<LineIntersection, Unrepresentable> {
+    Ok(match i {
+        LineIntersection::Parallel => LineIntersection::Parallel,
+        LineIntersection::Coincident => LineIntersection::Coincident,
+        LineIntersection::Point(p) => {
+            LineIntersection::Point(from_big_rational(&p).ok_or(Unrepresentable)?)
+        }
+    })
+}
+
+/// Big numbers never overflow, so their exact computations always succeed.
+fn unbounded<T>(result: Option<T>) -> T {
+    result.expect("big number arithmetic doesn't overflow")
+}
+
+fn sub_i64(a: Vec2i, b: Vec2i) -> Option<Vec2i> {
+    Some(Vec2i::new(a.x.checked_sub(b.x)?, a.y.checked_sub(b.y)?))
+}
+
+fn cross_i64(a: Vec2i, b: Vec2i) -> Option<i64> {
+    a.x.checked_mul(b.y)?.checked_sub(a.y.checked_mul(b.x)?)
+}
+
+/// `o + r * num / den` as an exact point, `None` on overflow.
+fn lerp_i64(o: Vec2i, r: Vec2i, num: i64, den: i64) -> Option<Vec2r128> {
+    let at = |o: i64, r: i64| {
+        let n = (o as i128 * den as i128).checked_add(r as i128 * num as i128)?;
+        Some(Rational128::new(n, den as i128))
+    };
+    Some(Vec2r128::new(at(o.x, r.x)?, at(o.y, r.y)?))
+}
+
+/// Checks if `num / den` lies in `[0, 1]` without dividing, `den` must not be zero.
+fn in_unit_interval(num: i64, den: i64) -> bool {
+    let (num, den) = (
+        num as i128 * den.signum() as i128,
+        den.unsigned_abs() as i128,
+    );
+    0 <= num && num <= den
+}
+
+fn sub<T: Exact>(a: &Vector2<T>, b: &Vector2<T>) -> Option<Vector2<T>> {
+    Some(Vector2::new(a.x.checked_sub(&b.x)?, a.y.checked_sub(&b.y)?))
+}
+
+fn cross<T: Exact>(a: &Vector2<T>, b: &Vector2<T>) -> Option<T> {
+    a.x.checked_mul(&b.y)?.checked_sub(&a.y.checked_mul(&b.x)?)
+}
+
+fn dot<T: Exact>(a: &Vector2<T>, b: &Vector2<T>) -> Option<T> {
+    a.x.checked_mul(&b.x)?.checked_add(&a.y.checked_mul(&b.y)?)
+}
+
+/// `o + r * t`, `None` on overflow.
+fn lerp<T: Exact>(o: &Vector2<T>, r: &Vector2<T>, t: &T) -> Option<Vector2<T>> {
+    let at = |o: &T, r: &T| o.checked_add(&r.checked_mul(t)?);
+    Some(Vector2::new(at(&o.x, &r.x)?, at(&o.y, &r.y)?))
+}
+
+/// The orientation of the turn `a -> b -> c`, as seen with the y-axis pointing down like [`crate::common::Direction`].
+///
+/// Computed in `i64` if possible and with big integers if that overflows.
+pub fn orientation(a: Vec2i, b: Vec2i, c: Vec2i) -> Orientation {
+    let fast = || cross_i64(sub_i64(b, a)?, sub_i64(c, a)?);
+    match fast() {
+        Some(cross) => Orientation::from_sign(cross.cmp(&0)),
+        None => {
+            let big = |v: Vec2i| v.map(BigInt::from);
+            unbounded(orientation_exact(&big(a), &big(b), &big(c)))
+        }
+    }
+}
+
+fn orientation_exact<T: Exact>(
+    a: &Vector2<T>,
+    b: &Vector2<T>,
+    c: &Vector2<T>,
+) -> Option<Orientation> {
+    Some(Orientation::from_sign(
+        cross(&sub(b, a)?, &sub(c, a)?)?.cmp(&T::zero()),
+    ))
+}
+
+/// Same as [`orientation`], but for rational points.
+pub fn orientation_r(a: &Vec2r128, b: &Vec2r128, c: &Vec2r128) -> Orientation {
+    orientation_exact(a, b, c)
+        .unwrap_or_else(|| unbounded(orientation_exact(&widen(a), &widen(b), &widen(c))))
+}
+
+/// Checks if `p` lies on the closed segment from `a` to `b`.
+pub fn point_on_segment(p: Vec2i, a: Vec2i, b: Vec2i) -> bool {
+    orientation(a, b, p) == Orientation::Collinear && Aabb2::from_corners(a, b).contains(&p)
+}
+
+fn point_on_segment_exact<T: Exact>(
+    p: &Vector2<T>,
+    a: &Vector2<T>,
+    b: &Vector2<T>,
+) -> Option<bool> {
+    Some(
+        orientation_exact(a, b, p)? == Orientation::Collinear
+            && (0..2).all(|i| {
+                a[i].clone().min(b[i].clone()) <= p[i] && p[i] <= a[i].clone().max(b[i].clone())
+            }),
+    )
+}
+
+/// Same as [`point_on_segment`], but for rational points.
+pub fn point_on_segment_r(p: &Vec2r128, a: &Vec2r128, b: &Vec2r128) -> bool {
+    point_on_segment_exact(p, a, b)
+        .unwrap_or_else(|| unbounded(point_on_segment_exact(&widen(p), &widen(a), &widen(b))))
+}
+
+/// Intersects the closed segments `p1 -> p2` and `q1 -> q2`.
+///
+/// Crossing segments are computed in `i64` if possible, everything else falls back to big rationals.
+pub fn segment_intersection(
+    p1: Vec2i,
+    p2: Vec2i,
+    q1: Vec2i,
+    q2: Vec2i,
+) -> Result<SegmentIntersection, Unrepresentable> {
+    let fast = || {
+        let (r, s, qp) = (sub_i64(p2, p1)?, sub_i64(q2, q1)?, sub_i64(q1, p1)?);
+        let den = cross_i64(r, s)?;
+        if den == 0 {
+            return None;
+        }
+        let (t, u) = (cross_i64(qp, s)?, cross_i64(qp, r)?);
+        if in_unit_interval(t, den) && in_unit_interval(u, den) {
+            Some(SegmentIntersection::Point(lerp_i64(p1, r, t, den)?))
+        } else {
+            Some(SegmentIntersection::Disjoint)
+        }
+    };
+    fast().map_or_else(
+        || {
+            from_big_segment(unbounded(segment_intersection_exact(
+                &to_big_rational(p1),
+                &to_big_rational(p2),
+                &to_big_rational(q1),
+                &to_big_rational(q2),
+            )))
+        },
+        Ok,
+    )
+}
+
+/// Same as [`segment_intersection`], but for rational points.
+///
+/// Computed in 128 bits if possible and with big rationals if that overflows.
+pub fn segment_intersection_r(
+    p1: &Vec2r128,
+    p2: &Vec2r128,
+    q1: &Vec2r128,
+    q2: &Vec2r128,
+) -> Result<SegmentIntersection, Unrepresentable> {
+    segment_intersection_exact(p1, p2, q1, q2).map_or_else(
+        || {
+            from_big_segment(unbounded(segment_intersection_exact(
+                &widen(p1),
+                &widen(p2),
+                &widen(q1),
+                &widen(q2),
+            )))
+        },
+        Ok,
+    )
+}
+
+/// The intersection computed in `T`, `None` on overflow.
+fn segment_intersection_exact<T: Exact>(
+    p1: &Vector2<T>,
+    p2: &Vector2<T>,
+    q1: &Vector2<T>,
+    q2: &Vector2<T>,
+) -> Option<SegmentIntersection<Vector2<T>>> {
+    let (r, s, qp) = (sub(p2, p1)?, sub(q2, q1)?, sub(q1, p1)?);
+    let den = cross(&r, &s)?;
+    if !den.is_zero() {
+        let t = cross(&qp, &s)?.checked_div(&den)?;
+        let u = cross(&qp, &r)?.checked_div(&den)?;
+        let in_unit_interval = |x: &T| T::zero() <= *x && *x <= T::one();
+        return Some(if in_unit_interval(&t) && in_unit_interval(&u) {
+            SegmentIntersection::Point(lerp(p1, &r, &t)?)
+        } else {
+            SegmentIntersection::Disjoint
+        });
+    }
+
+    // single points
+    let zero = Vector2::zeros();
+    if r == zero || s == zero {
+        let (p, a, b) = if r == zero {
+            (p1, q1, q2)
+        } else {
+            (q1, p1, p2)
+        };
+        return Some(if point_on_segment_exact(p, a, b)? {
+            SegmentIntersection::Point(p.clone())
+        } else {
+            SegmentIntersection::Disjoint
+        });
+    }
+
+    // parallel, but not on the same line
+    if !cross(&qp, &r)?.is_zero() {
+        return Some(SegmentIntersection::Disjoint);
+    }
+
+    // collinear: project q onto p1 + t * r and clamp to the segment p
+    let rr = dot(&r, &r)?;
+    let t0 = dot(&qp, &r)?.checked_div(&rr)?;
+    let t1 = dot(&sub(q2, p1)?, &r)?.checked_div(&rr)?;
+    let lo = t0.clone().min(t1.clone()).max(T::zero());
+    let hi = t0.max(t1).min(T::one());
+    Some(match lo.cmp(&hi) {
+        Ordering::Greater => SegmentIntersection::Disjoint,
+        Ordering::Equal => SegmentIntersection::Point(lerp(p1, &r, &lo)?),
+        Ordering::Less => SegmentIntersection::Overlap(lerp(p1, &r, &lo)?, lerp(p1, &r, &hi)?),
+    })
+}
+
+/// Intersects the line through `a` and `b` with the line through `c` and `d`, requires `a != b` and `c != d`.
+pub fn line_intersection(
+    a: Vec2i,
+    b: Vec2i,
+    c: Vec2i,
+    d: Vec2i,
+) -> Result<LineIntersection, Unrepresentable> {
+    let fast = || {
+        let (r, s, ca) = (sub_i64(b, a)?, sub_i64(d, c)?, sub_i64(c, a)?);
+        let den = cross_i64(r, s)?;
+        Some(if den != 0 {
+            LineIntersection::Point(lerp_i64(a, r, cross_i64(ca, s)?, den)?)
+        } else if cross_i64(ca, r)? == 0 {
+            LineIntersection::Coincident
+        } else {
+            LineIntersection::Parallel
+        })
+    };
+    fast().map_or_else(
+        || {
+            from_big_line(unbounded(line_intersection_exact(
+                &to_big_rational(a),
+                &to_big_rational(b),
+                &to_big_rational(c),
+                &to_big_rational(d),
+            )))
+        },
+        Ok,
+    )
+}
+
+/// Same as [`line_intersection`], but for rational points.
+///
+/// Computed in 128 bits if possible and with big rationals if that overflows.
+pub fn line_intersection_r(
+    a: &Vec2r128,
+    b: &Vec2r128,
+    c: &Vec2r128,
+    d: &Vec2r128,
+) -> Result<LineIntersection, Unrepresentable> {
+    line_intersection_exact(a, b, c, d).map_or_else(
+        || {
+            from_big_line(unbounded(line_intersection_exact(
+                &widen(a),
+                &widen(b),
+                &widen(c),
+                &widen(d),
+            )))
+        },
+        Ok,
+    )
+}
+
+/// The intersection computed in `T`, `None` on overflow.
+fn line_intersection_exact<T: Exact>(
+    a: &Vector2<T>,
+    b: &Vector2<T>,
+    c: &Vector2<T>,
+    d: &Vector2<T>,
+) -> Option<LineIntersection<Vector2<T>>> {
+    let (r, s, ca) = (sub(b, a)?, sub(d, c)?, sub(c, a)?);
+    // a + t * r = c + u * s
+    let m = Matrix2::from_columns(&[r.clone(), sub(&Vector2::zeros(), &s)?]);
+    Some(match solve2_exact(&m, &ca)? {
+        Some(tu) => LineIntersection::Point(lerp(a, &r, &tu.x)?),
+        None if cross(&ca, &r)?.is_zero() => LineIntersection::Coincident,
+        None => LineIntersection::Parallel,
+    })
+}
+
+/// Solves `m * x = rhs` with Cramer's rule, `Ok(None)` if `m` is singular.
+///
+/// Computed in 128 bits if possible and with big rationals if that overflows.
+pub fn solve2(m: &Mat2r128, rhs: &Vec2r128) -> Result<Option<Vec2r128>, Unrepresentable> {
+    solve2_exact(m, rhs).map_or_else(
+        || from_big_solution(unbounded(solve2_exact(&m.map(widen_scalar), &widen(rhs)))),
+        Ok,
+    )
+}
+
+fn from_big_solution(x: Option<Vector2<BigRational>>) -> Result<Option<Vec2r128>, Unrepresentable> {
+    x.map(|x| from_big_rational(&x).ok_or(Unrepresentable))
+        .transpose()
+}
+
+/// The solution computed in `T`, `None` on overflow and `Some(None)` if `m` is singular.
+fn solve2_exact<T: Exact>(m: &Matrix2<T>, rhs: &Vector2<T>) -> Option<Option<Vector2<T>>> {
+    let (c0, c1) = (m.column(0).into_owned(), m.column(1).into_owned());
+    let det = cross(&c0, &c1)?;
+    if det.is_zero() {
+        return Some(None);
+    }
+    Some(Some(Vector2::new(
+        cross(rhs, &c1)?.checked_div(&det)?,
+        cross(&c0, rhs)?.checked_div(&det)?,
+    )))
+}
+
+/// Same as [`solve2`], computed in `i64` if possible and with big rationals if that overflows.
+pub fn solve2_i64(m: &Mat2i, rhs: &Vec2i) -> Result<Option<Vec2r128>, Unrepresentable> {
+    let fast = || {
+        let (c0, c1) = (m.column(0).into_owned(), m.column(1).into_owned());
+        let det = cross_i64(c0, c1)?;
+        if det == 0 {
+            return Some(None);
+        }
+        let (x, y) = (cross_i64(*rhs, c1)?, cross_i64(c0, *rhs)?);
+        Some(Some(Vec2r128::new(
+            Rational128::new(x as i128, det as i128),
+            Rational128::new(y as i128, det as i128),
+        )))
+    };
+    fast().map_or_else(
+        || {
+            let m = m.map(|x| BigRational::from_integer(x.into()));
+            from_big_solution(unbounded(solve2_exact(&m, &to_big_rational(*rhs))))
+        },
+        Ok,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn r(x: i128, y: i128, den: i128) -> Vec2r128 {
+        Vec2r128::new(Rational128::new(x, den), Rational128::new(y, den))
+    }
+
+    #[test]
+    fn test_orientation() {
+        let (a, b) = (Vec2i::new(0, 0), Vec2i::new(4, 0));
+        // y points down, so turning towards positive y is clockwise
+        assert_eq!(orientation(a, b, Vec2i::new(2, 1)), Orientation::Clockwise);
+        assert_eq!(
+            orientation(a, b, Vec2i::new(2, -1)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(orientation(a, b, Vec2i::new(9, 0)), Orientation::Collinear);
+
+        let big = i64::MAX / 2;
+        let (a, b, c) = (
+            Vec2i::new(-big, -big),
+            Vec2i::new(big, big),
+            Vec2i::new(big - 1, big),
+        );
+        assert_eq!(orientation(a, b, c), Orientation::Clockwise);
+        assert_eq!(orientation(a, b, Vec2i::new(0, 0)), Orientation::Collinear);
+
+        // differences and cross products of the full range overflow i64 and i128
+        let (min, max) = (i64::MIN, i64::MAX);
+        let (a, b, c) = (
+            Vec2i::new(min, min),
+            Vec2i::new(max, min),
+            Vec2i::new(min, max),
+        );
+        assert_eq!(orientation(a, b, c), Orientation::Clockwise);
+        assert_eq!(orientation(a, c, b), Orientation::CounterClockwise);
+        assert_eq!(
+            orientation(a, Vec2i::new(max, max), Vec2i::new(0, 0)),
+            Orientation::Collinear
+        );
+        assert!(point_on_segment(
+            Vec2i::new(-1, -1),
+            a,
+            Vec2i::new(max, max)
+        ));
+
+        assert!(point_on_segment(
+            Vec2i::new(2, 0),
+            Vec2i::new(0, 0),
+            Vec2i::new(4, 0)
+        ));
+        assert!(!point_on_segment(
+            Vec2i::new(5, 0),
+            Vec2i::new(0, 0),
+            Vec2i::new(4, 0)
+        ));
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let v = Vec2i::new;
+        assert_eq!(
+            segment_intersection(v(0, 0), v(3, 3), v(0, 1), v(3, 1)),
+            Ok(SegmentIntersection::Point(r(1, 1, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(1, 2), v(1, 0), v(0, 1)),
+            Ok(SegmentIntersection::Point(r(1, 2, 3)))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(1, 1), v(2, 0), v(3, -1)),
+            Ok(SegmentIntersection::Disjoint)
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(4, 2), v(6, 3), v(2, 1)),
+            Ok(SegmentIntersection::Overlap(r(2, 1, 1), r(4, 2, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(4, 2), v(4, 2), v(6, 3)),
+            Ok(SegmentIntersection::Point(r(4, 2, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(4, 2), v(1, 1), v(5, 3)),
+            Ok(SegmentIntersection::Disjoint)
+        );
+        assert_eq!(
+            segment_intersection(v(2, 1), v(2, 1), v(0, 0), v(4, 2)),
+            Ok(SegmentIntersection::Point(r(2, 1, 1)))
+        );
+
+        // overflows i64 and falls back to rationals
+        let big = i64::MAX / 2;
+        assert_eq!(
+            segment_intersection(v(-big, 0), v(big, 0), v(0, -big), v(0, big)),
+            Ok(SegmentIntersection::Point(r(0, 0, 1)))
+        );
+
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(
+            segment_intersection(v(min, min), v(max, max), v(min, max), v(max, min)),
+            Ok(SegmentIntersection::Point(r(-1, -1, 2)))
+        );
+        assert_eq!(
+            segment_intersection(v(min, 0), v(max, 0), v(0, min), v(0, max)),
+            Ok(SegmentIntersection::Point(r(0, 0, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(min, min), v(max, max), v(max, max), v(0, 0)),
+            Ok(SegmentIntersection::Overlap(
+                r(0, 0, 1),
+                r(max as i128, max as i128, 1)
+            ))
+        );
+        assert_eq!(
+            segment_intersection(v(min, min), v(max, min), v(min, max), v(max, max)),
+            Ok(SegmentIntersection::Disjoint)
+        );
+    }
+
+    #[test]
+    fn test_line_intersection() {
+        let v = Vec2i::new;
+        assert_eq!(
+            line_intersection(v(0, 0), v(1, 2), v(1, 0), v(0, 1)),
+            Ok(LineIntersection::Point(r(1, 2, 3)))
+        );
+        assert_eq!(
+            line_intersection(v(0, 0), v(1, 1), v(5, 0), v(6, 1)),
+            Ok(LineIntersection::Parallel)
+        );
+        assert_eq!(
+            line_intersection(v(0, 0), v(1, 1), v(5, 5), v(6, 6)),
+            Ok(LineIntersection::Coincident)
+        );
+        let big = i64::MAX / 2;
+        assert_eq!(
+            line_intersection(v(-big, 1), v(big, 1), v(3, -big), v(3, big)),
+            Ok(LineIntersection::Point(r(3, 1, 1)))
+        );
+
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(
+            line_intersection(v(min, min), v(max, max), v(min, max), v(max, min)),
+            Ok(LineIntersection::Point(r(-1, -1, 2)))
+        );
+        assert_eq!(
+            line_intersection(v(min, min), v(max, max), v(min, min + 1), v(max, max)),
+            Ok(LineIntersection::Point(r(max as i128, max as i128, 1)))
+        );
+        assert_eq!(
+            line_intersection(v(min, min), v(max, max), v(min + 1, min), v(max, max - 1)),
+            Ok(LineIntersection::Parallel)
+        );
+    }
+
+    #[test]
+    fn test_solve2() {
+        let m = Mat2i::new(2, 1, 1, 3);
+        assert_eq!(solve2_i64(&m, &Vec2i::new(3, 5)), Ok(Some(r(4, 7, 5))));
+        assert_eq!(
+            solve2_i64(&Mat2i::new(1, 2, 2, 4), &Vec2i::new(1, 1)),
+            Ok(None)
+        );
+
+        let m = Mat2i::new(i64::MAX, 1, 1, i64::MAX);
+        let x = solve2_i64(&m, &Vec2i::new(i64::MAX, 1)).unwrap().unwrap();
+        assert_eq!(x, r(1, 0, 1));
+
+        let m = Mat2i::new(i64::MIN, i64::MAX, i64::MAX, i64::MIN);
+        let x = solve2_i64(&m, &Vec2i::new(-1, -1)).unwrap().unwrap();
+        assert_eq!(x, r(1, 1, 1));
+    }
+
+    #[test]
+    fn test_rational_fallback() {
+        let v = |x: i128, y: i128| r(x, y, 1);
+        // the cross products overflow 128 bits and fall back to big rationals
+        let big = i128::MAX / 2;
+        assert_eq!(
+            orientation_r(&v(-big, -big), &v(big, big), &v(big - 1, big)),
+            Orientation::Clockwise
+        );
+        assert!(point_on_segment_r(&v(0, 0), &v(-big, -big), &v(big, big)));
+        assert_eq!(
+            segment_intersection_r(&v(-big, 0), &v(big, 0), &v(0, -big), &v(0, big)),
+            Ok(SegmentIntersection::Point(v(0, 0)))
+        );
+        assert_eq!(
+            line_intersection_r(&v(-big, 1), &v(big, 1), &v(3, -big), &v(3, big)),
+            Ok(LineIntersection::Point(v(3, 1)))
+        );
+        let m = Mat2r128::new(big.into(), 1.into(), 1.into(), big.into());
+        assert_eq!(solve2(&m, &v(big, 1)), Ok(Some(v(1, 0))));
+
+        // crosses the x-axis at (1 / a + 1 / b) / 2, which needs a 201 bit denominator
+        let (a, b) = (1 << 100, (1 << 100) + 1);
+        let c = Vec2r128::new(Rational128::new(1, a), 1.into());
+        let d = Vec2r128::new(Rational128::new(1, b), (-1).into());
+        assert_eq!(
+            line_intersection_r(&v(0, 0), &v(1, 0), &c, &d),
+            Err(Unrepresentable)
+        );
+    }
+}
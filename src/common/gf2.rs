@@ -0,0 +1,297 @@
+use crate::common::BitSet;
+
+/// A matrix over GF(2) with arbitrary width, each row is packed into `u64` words.
+///
+/// Rows are packed like a [`BitSet`], which is used for vectors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gf2Matrix {
+    rows: usize,
+    cols: usize,
+    words: usize,
+    data: Vec<u64>,
+}
+
+/// The number of `u64` words needed for a vector of `len` bits.
+pub fn gf2_words(len: usize) -> usize {
+    len.div_ceil(u64::BITS as usize)
+}
+
+pub fn gf2_get(v: &[u64], i: usize) -> bool {
+    (v[i / 64] >> (i % 64)) & 1 != 0
+}
+
+pub fn gf2_flip(v: &mut [u64], i: usize) {
+    v[i / 64] ^= 1 << (i % 64);
+}
+
+fn xor_into(dst: &mut [u64], src: &[u64]) {
+    dst.iter_mut().zip(src).for_each(|(d, s)| *d ^= s);
+}
+
+impl Gf2Matrix {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words = gf2_words(cols);
+        Self {
+            rows,
+            cols,
+            words,
+            data: vec![0; rows * words],
+        }
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> bool) -> Self {
+        let mut m = Self::new(rows, cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                m.set(r, c, f(r, c));
+            }
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, r: usize) -> &[u64] {
+        &self.data[r * self.words..(r + 1) * self.words]
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        gf2_get(self.row(r), c)
+    }
+
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        if self.get(r, c) != value {
+            gf2_flip(&mut self.data[r * self.words..(r + 1) * self.words], c);
+        }
+    }
+
+    /// Adds row `src` to row `dst`.
+    fn xor_rows(&mut self, dst: usize, src: usize) {
+        let (lo, hi) = self.data.split_at_mut(dst.max(src) * self.words);
+        let (a, b) = (
+            &mut lo[dst.min(src) * self.words..][..self.words],
+            &mut hi[..self.words],
+        );
+        if dst < src {
+            xor_into(a, b);
+        } else {
+            xor_into(b, a);
+        }
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for w in 0..self.words {
+            self.data.swap(a * self.words + w, b * self.words + w);
+        }
+    }
+
+    /// Brings the matrix into reduced row echelon form and returns the pivot column of each nonzero row.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        for c in 0..self.cols {
+            let r = pivots.len();
+            if r == self.rows {
+                break;
+            }
+            let Some(p) = (r..self.rows).find(|&i| self.get(i, c)) else {
+                continue;
+            };
+            self.swap_rows(r, p);
+            for i in 0..self.rows {
+                if i != r && self.get(i, c) {
+                    self.xor_rows(i, r);
+                }
+            }
+            pivots.push(c);
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    /// A basis of all `x` with `self * x = 0`.
+    pub fn null_space(&self) -> Vec<BitSet> {
+        let mut m = self.clone();
+        let pivots = m.row_reduce();
+        m.null_space_reduced(&pivots)
+    }
+
+    /// The null space of a matrix in reduced row echelon form: one vector for every free column.
+    fn null_space_reduced(&self, pivots: &[usize]) -> Vec<BitSet> {
+        let mut free = vec![true; self.cols];
+        pivots.iter().for_each(|&c| free[c] = false);
+        (0..self.cols)
+            .filter(|&f| free[f])
+            .map(|f| {
+                let mut v = BitSet::new(self.cols);
+                v.flip(f);
+                for (r, &c) in pivots.iter().enumerate() {
+                    if self.get(r, f) {
+                        v.flip(c);
+                    }
+                }
+                v
+            })
+            .collect()
+    }
+
+    /// All solutions `x` of `self * x = rhs`, `None` if there are none.
+    pub fn solve(&self, rhs: &BitSet) -> Option<Gf2Solutions> {
+        assert_eq!(rhs.len(), self.rows);
+        // reduce the augmented matrix [self | rhs]
+        let mut m = Self::from_fn(self.rows, self.cols + 1, |r, c| {
+            if c < self.cols {
+                self.get(r, c)
+            } else {
+                rhs.get(r)
+            }
+        });
+        let pivots = m.row_reduce();
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+
+        let mut particular = BitSet::new(self.cols);
+        for (r, &c) in pivots.iter().enumerate() {
+            if m.get(r, self.cols) {
+                particular.flip(c);
+            }
+        }
+        let null_space = m
+            .null_space_reduced(&pivots)
+            .into_iter()
+            .filter(|v| !v.get(self.cols))
+            .map(|mut v| {
+                v.resize(self.cols);
+                v
+            })
+            .collect();
+        Some(Gf2Solutions {
+            particular,
+            null_space,
+        })
+    }
+}
+
+/// The solutions of a linear system: `particular` plus any sum of vectors from `null_space`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gf2Solutions {
+    pub particular: BitSet,
+    pub null_space: Vec<BitSet>,
+}
+
+impl Gf2Solutions {
+    /// The number of distinct solutions, `None` if it does not fit into `u128`.
+    pub fn count(&self) -> Option<u128> {
+        1u128.checked_shl(self.null_space.len() as u32)
+    }
+
+    /// The largest null space dimension [`Gf2Solutions::min_weight`] searches, about 16 million
+    /// solutions.
+    pub const MAX_MIN_WEIGHT_DIMENSION: usize = 24;
+
+    /// A solution with the fewest set bits, visiting all solutions in Gray code order.
+    ///
+    /// This is exponential in the dimension of the null space, so it gives up with `None` if that
+    /// exceeds [`Gf2Solutions::MAX_MIN_WEIGHT_DIMENSION`].
+    pub fn min_weight(&self) -> Option<BitSet> {
+        if self.null_space.len() > Self::MAX_MIN_WEIGHT_DIMENSION {
+            return None;
+        }
+        let mut current = self.particular.clone();
+        let mut best = (current.count_ones(), current.clone());
+        for i in 1..1u64 << self.null_space.len() {
+            current ^= &self.null_space[i.trailing_zeros() as usize];
+            let weight = current.count_ones();
+            if weight < best.0 {
+                best = (weight, current.clone());
+            }
+        }
+        Some(best.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn matrix(rows: &[&str]) -> Gf2Matrix {
+        Gf2Matrix::from_fn(rows.len(), rows[0].len(), |r, c| {
+            rows[r].as_bytes()[c] == b'1'
+        })
+    }
+
+    fn mul(m: &Gf2Matrix, x: &BitSet) -> BitSet {
+        BitSet::from_fn(m.rows(), |r| {
+            let dot = m
+                .row(r)
+                .iter()
+                .zip(x.words())
+                .map(|(a, b)| (a & b).count_ones())
+                .sum::<u32>();
+            dot % 2 == 1
+        })
+    }
+
+    fn bits(len: usize, word: u64) -> BitSet {
+        BitSet::from_words(len, &[word])
+    }
+
+    #[test]
+    fn test_row_reduce() {
+        let mut m = matrix(&["1101", "0111", "1010"]);
+        assert_eq!(m.rank(), 2);
+        assert_eq!(m.row_reduce(), [0, 1]);
+        assert_eq!(m, matrix(&["1010", "0111", "0000"]));
+
+        let null_space = matrix(&["1101", "0111", "1010"]).null_space();
+        assert_eq!(null_space, [bits(4, 0b0111), bits(4, 0b1010)]);
+    }
+
+    #[test]
+    fn test_solve() {
+        let m = matrix(&["1101", "0111", "1010"]);
+        assert_eq!(m.solve(&bits(3, 0b001)), None);
+
+        let solutions = m.solve(&bits(3, 0b101)).unwrap();
+        assert_eq!(solutions.count(), Some(4));
+        assert_eq!(mul(&m, &solutions.particular), bits(3, 0b101));
+        for v in &solutions.null_space {
+            assert_eq!(mul(&m, v), BitSet::new(3));
+        }
+        let min = solutions.min_weight().unwrap();
+        assert_eq!(mul(&m, &min), bits(3, 0b101));
+        assert_eq!(min.count_ones(), 1);
+    }
+
+    #[test]
+    fn test_wide() {
+        // the identity with an extra all-ones column, spanning multiple words
+        let n = 100;
+        let m = Gf2Matrix::from_fn(n, n + 1, |r, c| r == c || c == n);
+        assert_eq!(m.rank(), n);
+        let rhs = BitSet::from_fn(n, |_| true);
+        let solutions = m.solve(&rhs).unwrap();
+        assert_eq!(solutions.null_space.len(), 1);
+        assert_eq!(solutions.min_weight().unwrap().count_ones(), 1);
+    }
+
+    #[test]
+    fn test_min_weight_limit() {
+        // a single equation with every other variable free
+        let dimension = Gf2Solutions::MAX_MIN_WEIGHT_DIMENSION + 1;
+        let m = Gf2Matrix::from_fn(1, dimension + 1, |_, c| c == 0);
+        let solutions = m.solve(&bits(1, 1)).unwrap();
+        assert_eq!(solutions.null_space.len(), dimension);
+        assert_eq!(solutions.min_weight(), None);
+    }
+}
@@ -0,0 +1,533 @@
+use num::{BigInt, BigRational, One, Signed, Zero};
+use thiserror::Error;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Error)]
+pub enum IlpError {
+    #[error("no integer solution satisfies the constraints")]
+    Infeasible,
+    #[error("the objective is unbounded from below")]
+    Unbounded,
+}
+
+/// Minimizes `objective · x` subject to equality constraints and `x >= 0`, with `x` integer.
+///
+/// Everything is computed exactly with big rationals: every node of the branch-and-bound search
+/// solves its linear relaxation with the simplex method, using Bland's rule to avoid cycling.
+/// Inequalities can be expressed by adding a slack variable with zero cost.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntegerProgram {
+    objective: Vec<i64>,
+    constraints: Vec<(Vec<i64>, i64)>,
+}
+
+/// An optimal solution together with a proof of its optimality, see [`IntegerProgram::verify`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IlpSolution {
+    pub x: Vec<i64>,
+    pub objective: i64,
+    pub certificate: Certificate,
+}
+
+/// The branch-and-bound tree of the search, the root is the first node.
+///
+/// Every leaf carries dual values for the constraints, which bound the objective of all
+/// integer points in its box without trusting the solver.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Certificate {
+    pub nodes: Vec<CertificateNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CertificateNode {
+    /// Splits the box into `x[variable] <= floor` and `x[variable] >= floor + 1`,
+    /// the children come after this node.
+    Branch {
+        variable: usize,
+        floor: i64,
+        down: usize,
+        up: usize,
+    },
+    /// Dual values whose bound is no better than the solution, see [`IntegerProgram::dual_bound`].
+    Bound(Vec<BigRational>),
+    /// Dual values proving that no point in the box satisfies the constraints,
+    /// empty if the box itself is empty.
+    Infeasible(Vec<BigRational>),
+}
+
+/// The outcome of a linear relaxation, with dual values for the constraints proving it.
+enum Relaxation {
+    Optimal {
+        value: BigRational,
+        x: Vec<BigRational>,
+        duals: Vec<BigRational>,
+    },
+    Infeasible(Vec<BigRational>),
+}
+
+fn big(v: i64) -> BigRational {
+    BigRational::from_integer(v.into())
+}
+
+impl IntegerProgram {
+    pub fn new(objective: Vec<i64>) -> Self {
+        Self {
+            objective,
+            constraints: vec![],
+        }
+    }
+
+    pub fn variables(&self) -> usize {
+        self.objective.len()
+    }
+
+    /// Adds the constraint `coefficients · x = rhs`.
+    pub fn add_equality(&mut self, coefficients: Vec<i64>, rhs: i64) {
+        assert_eq!(coefficients.len(), self.variables());
+        self.constraints.push((coefficients, rhs));
+    }
+
+    pub fn is_feasible(&self, x: &[i64]) -> bool {
+        x.len() == self.variables()
+            && x.iter().all(|&v| v >= 0)
+            && self.constraints.iter().all(|(a, b)| {
+                a.iter()
+                    .zip(x)
+                    .map(|(&a, &x)| a as i128 * x as i128)
+                    .sum::<i128>()
+                    == *b as i128
+            })
+    }
+
+    pub fn solve(&self) -> Result<IlpSolution, IlpError> {
+        let n = self.variables();
+        let mut best: Option<(i64, Vec<i64>)> = None;
+        let mut nodes = vec![None];
+
+        // depth-first over (node, lower bounds, upper bounds)
+        let mut stack = vec![(0, vec![0; n], vec![None; n])];
+        while let Some((node, lo, hi)) = stack.pop() {
+            let (value, x, duals) = match self.relaxation(&lo, &hi)? {
+                Relaxation::Optimal { value, x, duals } => (value, x, duals),
+                Relaxation::Infeasible(duals) => {
+                    nodes[node] = Some(CertificateNode::Infeasible(duals));
+                    continue;
+                }
+            };
+            // the objective has integer coefficients, so integer solutions can only reach the next integer
+            let bound = value.ceil();
+            if best.as_ref().is_some_and(|(b, _)| bound >= big(*b)) {
+                nodes[node] = Some(CertificateNode::Bound(duals));
+                continue;
+            }
+
+            let Some(j) = (0..n).find(|&j| !x[j].is_integer()) else {
+                let x = x.iter().map(|v| to_i64(v.numer())).collect();
+                best = Some((to_i64(bound.numer()), x));
+                nodes[node] = Some(CertificateNode::Bound(duals));
+                continue;
+            };
+            let floor = to_i64(&x[j].floor().to_integer());
+            let (down, up) = (nodes.len(), nodes.len() + 1);
+            nodes.extend([None, None]);
+            nodes[node] = Some(CertificateNode::Branch {
+                variable: j,
+                floor,
+                down,
+                up,
+            });
+            let (mut up_lo, mut down_hi) = (lo.clone(), hi.clone());
+            up_lo[j] = floor + 1;
+            down_hi[j] = Some(floor);
+            stack.push((up, up_lo, hi));
+            stack.push((down, lo, down_hi));
+        }
+
+        let (objective, x) = best.ok_or(IlpError::Infeasible)?;
+        Ok(IlpSolution {
+            x,
+            objective,
+            certificate: Certificate {
+                nodes: nodes.into_iter().map(Option::unwrap).collect(),
+            },
+        })
+    }
+
+    /// A lower bound on `objective · x` over all `x` with `lo <= x <= hi` satisfying the constraints,
+    /// given any dual values `y` for the constraints, `None` if it is unbounded.
+    ///
+    /// Every such `x` has `objective · x = y · b + (objective - yA) · x`, and the last term is bounded
+    /// for every variable separately. Without `use_cost` the objective is zero, so a positive bound
+    /// proves that no `x` satisfies the constraints.
+    pub fn dual_bound(
+        &self,
+        use_cost: bool,
+        lo: &[i64],
+        hi: &[Option<i64>],
+        y: &[BigRational],
+    ) -> Option<BigRational> {
+        if y.len() != self.constraints.len() {
+            return None;
+        }
+        let mut bound: BigRational = self
+            .constraints
+            .iter()
+            .zip(y)
+            .map(|((_, b), y)| y * big(*b))
+            .sum();
+        for j in 0..self.variables() {
+            let cost = if use_cost { self.objective[j] } else { 0 };
+            let reduced = self
+                .constraints
+                .iter()
+                .zip(y)
+                .fold(big(cost), |d, ((a, _), y)| d - y * big(a[j]));
+            if reduced.is_positive() {
+                bound += reduced * big(lo[j]);
+            } else if reduced.is_negative() {
+                bound += reduced * big(hi[j]?);
+            }
+        }
+        Some(bound)
+    }
+
+    /// Checks that the solution is feasible and that its certificate proves optimality.
+    pub fn verify(&self, solution: &IlpSolution) -> bool {
+        let n = self.variables();
+        let objective: i128 = self
+            .objective
+            .iter()
+            .zip(&solution.x)
+            .map(|(&c, &x)| c as i128 * x as i128)
+            .sum();
+        if !self.is_feasible(&solution.x) || objective != solution.objective as i128 {
+            return false;
+        }
+
+        let target = big(solution.objective);
+        let nodes = &solution.certificate.nodes;
+        let mut stack = vec![(0, vec![0; n], vec![None; n])];
+        while let Some((node, lo, hi)) = stack.pop() {
+            match nodes.get(node) {
+                Some(&CertificateNode::Branch {
+                    variable,
+                    floor,
+                    down,
+                    up,
+                }) => {
+                    // children after their parent make the tree finite
+                    if variable >= n || down <= node || up <= node {
+                        return false;
+                    }
+                    let (mut up_lo, mut down_hi) = (lo.clone(), hi.clone());
+                    up_lo[variable] = lo[variable].max(floor + 1);
+                    down_hi[variable] = Some(hi[variable].map_or(floor, |h: i64| h.min(floor)));
+                    stack.push((up, up_lo, hi));
+                    stack.push((down, lo, down_hi));
+                }
+                Some(CertificateNode::Bound(y)) => {
+                    if self
+                        .dual_bound(true, &lo, &hi, y)
+                        .is_none_or(|bound| bound.ceil() < target)
+                    {
+                        return false;
+                    }
+                }
+                Some(CertificateNode::Infeasible(y)) => {
+                    let empty = (0..n).any(|j| hi[j].is_some_and(|h| h < lo[j]));
+                    if !empty
+                        && !self
+                            .dual_bound(false, &lo, &hi, y)
+                            .is_some_and(|bound| bound.is_positive())
+                    {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Solves the linear relaxation with `lo <= x <= hi`.
+    fn relaxation(&self, lo: &[i64], hi: &[Option<i64>]) -> Result<Relaxation, IlpError> {
+        let (n, m) = (self.variables(), self.constraints.len());
+
+        // substitute x = lo + y with y >= 0, every upper bound becomes y_j + s = hi_j - lo_j
+        let mut rows = vec![];
+        for (a, b) in &self.constraints {
+            let shifted: i128 = a.iter().zip(lo).map(|(&a, &l)| a as i128 * l as i128).sum();
+            let mut row: Vec<_> = a.iter().map(|&a| big(a)).collect();
+            row.push(BigRational::from_integer(BigInt::from(*b) - shifted));
+            rows.push(row);
+        }
+        let bounded: Vec<_> = (0..n).filter_map(|j| Some((j, hi[j]? - lo[j]))).collect();
+        if bounded.iter().any(|&(_, range)| range < 0) {
+            return Ok(Relaxation::Infeasible(vec![]));
+        }
+        let cols = n + bounded.len();
+        for row in &mut rows {
+            row.splice(n..n, (0..bounded.len()).map(|_| BigRational::zero()));
+        }
+        for (k, &(j, range)) in bounded.iter().enumerate() {
+            let mut row = vec![BigRational::zero(); cols + 1];
+            row[j] = BigRational::one();
+            row[n + k] = BigRational::one();
+            row[cols] = big(range);
+            rows.push(row);
+        }
+
+        let mut cost: Vec<_> = self.objective.iter().map(|&c| big(c)).collect();
+        cost.resize(cols, BigRational::zero());
+        // only the duals of the real constraints are needed, the bounds are implied by the box
+        Ok(match Simplex::minimize(rows, &cost)? {
+            Lp::Optimal(y, mut duals) => {
+                duals.truncate(m);
+                let x: Vec<_> = (0..n).map(|j| &y[j] + big(lo[j])).collect();
+                let value = (0..n).map(|j| &cost[j] * &x[j]).sum();
+                Relaxation::Optimal { value, x, duals }
+            }
+            Lp::Infeasible(mut duals) => {
+                duals.truncate(m);
+                Relaxation::Infeasible(duals)
+            }
+        })
+    }
+}
+
+fn to_i64(v: &BigInt) -> i64 {
+    i64::try_from(v).expect("integer solution doesn't fit into an i64")
+}
+
+/// The outcome of a linear program, with dual values for every row.
+enum Lp {
+    /// The optimal point, and duals whose bound equals its value.
+    Optimal(Vec<BigRational>, Vec<BigRational>),
+    /// Duals `y` with `yA <= 0` and `y · b > 0`, a Farkas certificate.
+    Infeasible(Vec<BigRational>),
+}
+
+/// A dense simplex tableau, the last entry of every row is its right hand side.
+///
+/// Next to the real columns, every row keeps the columns of the artificial variables.
+/// They start out as the identity, so they record how every row combines the original rows,
+/// and the reduced costs in these columns are the dual values.
+struct Simplex {
+    rows: Vec<Vec<BigRational>>,
+    basis: Vec<usize>,
+    /// The reduced costs, with the negated objective value as the last entry.
+    objective: Vec<BigRational>,
+}
+
+impl Simplex {
+    /// Minimizes `cost · x` subject to `rows` and `x >= 0` with the two-phase method.
+    fn minimize(mut rows: Vec<Vec<BigRational>>, cost: &[BigRational]) -> Result<Lp, IlpError> {
+        let (m, n) = (rows.len(), cost.len());
+
+        // phase 1: minimize the sum of one artificial variable per row, starting from the artificial basis
+        let mut signs = vec![BigRational::one(); m];
+        for (i, row) in rows.iter_mut().enumerate() {
+            if row[n].is_negative() {
+                row.iter_mut().for_each(|v| *v = -v.clone());
+                signs[i] = -BigRational::one();
+            }
+            row.splice(
+                n..n,
+                (0..m).map(|k| {
+                    if k == i {
+                        BigRational::one()
+                    } else {
+                        BigRational::zero()
+                    }
+                }),
+            );
+        }
+        let mut objective = vec![BigRational::zero(); n + m + 1];
+        for row in &rows {
+            for j in (0..n).chain([n + m]) {
+                objective[j] -= &row[j];
+            }
+        }
+        let mut tableau = Self {
+            rows,
+            basis: (n..n + m).collect(),
+            objective,
+        };
+        tableau.optimize(n + m)?;
+        if !tableau.objective[n + m].is_zero() {
+            // the artificial variables cost one each
+            return Ok(Lp::Infeasible(tableau.duals(n, &signs, BigRational::one())));
+        }
+
+        // drive the remaining artificial variables out of the basis, rows without any other entry are redundant
+        let mut i = 0;
+        while i < tableau.rows.len() {
+            if tableau.basis[i] >= n {
+                match (0..n).find(|&j| !tableau.rows[i][j].is_zero()) {
+                    Some(j) => tableau.pivot(i, j),
+                    None => {
+                        tableau.rows.remove(i);
+                        tableau.basis.remove(i);
+                        continue;
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        // phase 2: the real cost, expressed in terms of the non-basic variables
+        let mut objective: Vec<_> = cost.to_vec();
+        objective.resize(n + m + 1, BigRational::zero());
+        for (row, &b) in tableau.rows.iter().zip(&tableau.basis) {
+            let c = objective[b].clone();
+            if !c.is_zero() {
+                objective
+                    .iter_mut()
+                    .zip(row)
+                    .for_each(|(o, v)| *o -= &c * v);
+            }
+        }
+        tableau.objective = objective;
+        tableau.optimize(n)?;
+
+        let mut x = vec![BigRational::zero(); n];
+        for (row, &b) in tableau.rows.iter().zip(&tableau.basis) {
+            x[b] = row[n + m].clone();
+        }
+        let duals = tableau.duals(n, &signs, BigRational::zero());
+        Ok(Lp::Optimal(x, duals))
+    }
+
+    /// The dual values of the original rows, where every artificial variable has the given cost.
+    fn duals(&self, n: usize, signs: &[BigRational], cost: BigRational) -> Vec<BigRational> {
+        signs
+            .iter()
+            .enumerate()
+            .map(|(k, sign)| (&cost - &self.objective[n + k]) * sign)
+            .collect()
+    }
+
+    fn pivot(&mut self, r: usize, c: usize) {
+        let p = self.rows[r][c].clone();
+        self.rows[r].iter_mut().for_each(|v| *v /= &p);
+        let pivot_row = self.rows[r].clone();
+        let others = self
+            .rows
+            .iter_mut()
+            .enumerate()
+            .filter(|&(i, _)| i != r)
+            .map(|(_, row)| row)
+            .chain([&mut self.objective]);
+        for row in others {
+            let f = row[c].clone();
+            if !f.is_zero() {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(v, p)| *v -= &f * p);
+            }
+        }
+        self.basis[r] = c;
+    }
+
+    /// Pivots until no reduced cost among the first `allowed` columns is negative.
+    ///
+    /// Bland's rule picks the entering column and breaks ties in the ratio test by the smallest basic variable,
+    /// which guarantees termination.
+    fn optimize(&mut self, allowed: usize) -> Result<(), IlpError> {
+        let rhs = self.objective.len() - 1;
+        while let Some(c) = (0..allowed).find(|&j| self.objective[j].is_negative()) {
+            let r = (0..self.rows.len())
+                .filter(|&i| self.rows[i][c].is_positive())
+                .min_by_key(|&i| (&self.rows[i][rhs] / &self.rows[i][c], self.basis[i]))
+                .ok_or(IlpError::Unbounded)?;
+            self.pivot(r, c);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_solve() {
+        // x0 + x1 = 3, x1 + x2 = 5, min x0 + x1 + x2
+        let mut p = IntegerProgram::new(vec![1, 1, 1]);
+        p.add_equality(vec![1, 1, 0], 3);
+        p.add_equality(vec![0, 1, 1], 5);
+        let s = p.solve().unwrap();
+        assert_eq!(s.x, [0, 3, 2]);
+        assert_eq!(s.objective, 5);
+        assert!(p.is_feasible(&s.x));
+        assert!(p.verify(&s));
+
+        // neither a worse solution nor a certificate without useful duals verifies
+        let mut wrong = s.clone();
+        wrong.x = vec![1, 2, 3];
+        wrong.objective = 6;
+        assert!(!p.verify(&wrong));
+        let mut wrong = s.clone();
+        wrong.certificate.nodes = vec![CertificateNode::Bound(vec![BigRational::zero(); 2])];
+        assert!(!p.verify(&wrong));
+        let mut wrong = s;
+        wrong.certificate.nodes = vec![CertificateNode::Infeasible(vec![])];
+        assert!(!p.verify(&wrong));
+    }
+
+    #[test]
+    fn test_branching() {
+        // 2 x0 >= 3 and 2 x1 >= 3, the relaxation is optimal at x = (1.5, 1.5) with value 3
+        let mut p = IntegerProgram::new(vec![1, 1, 0, 0]);
+        p.add_equality(vec![2, 0, -1, 0], 3);
+        p.add_equality(vec![0, 2, 0, -1], 3);
+        let s = p.solve().unwrap();
+        assert_eq!(s.x, [2, 2, 1, 1]);
+        assert_eq!(s.objective, 4);
+        assert!(s.certificate.nodes.len() > 1);
+        assert!(p.verify(&s));
+        // the duals of the relaxation alone only prove a bound of 3
+        let half = BigRational::new(1.into(), 2.into());
+        assert_eq!(
+            p.dual_bound(true, &[0; 4], &[None; 4], &[half.clone(), half]),
+            Some(BigRational::from_integer(3.into()))
+        );
+
+        // 2 x0 + 2 x1 = 5 has rational, but no integer solutions
+        let mut p = IntegerProgram::new(vec![1, 1]);
+        p.add_equality(vec![2, 2], 5);
+        assert_eq!(p.solve(), Err(IlpError::Infeasible));
+        // with x <= 1 even 2 x0 + 2 x1 can only reach 4
+        let one = BigRational::one();
+        assert_eq!(
+            p.dual_bound(
+                false,
+                &[0, 0],
+                &[Some(1), Some(1)],
+                std::slice::from_ref(&one)
+            ),
+            Some(one)
+        );
+    }
+
+    #[test]
+    fn test_degenerate() {
+        // redundant and negative right hand sides
+        let mut p = IntegerProgram::new(vec![3, 1, 2]);
+        p.add_equality(vec![1, 1, 1], 4);
+        p.add_equality(vec![-1, -1, -1], -4);
+        p.add_equality(vec![1, 0, -1], 0);
+        let s = p.solve().unwrap();
+        assert_eq!(s.x, [0, 4, 0]);
+        assert_eq!(s.objective, 4);
+        assert!(p.verify(&s));
+
+        let mut p = IntegerProgram::new(vec![-1, 0]);
+        p.add_equality(vec![1, -1], 0);
+        assert_eq!(p.solve(), Err(IlpError::Unbounded));
+
+        let mut p = IntegerProgram::new(vec![1, 1]);
+        p.add_equality(vec![1, 1], -1);
+        assert_eq!(p.solve(), Err(IlpError::Infeasible));
+    }
+}
@@ -0,0 +1,119 @@
+use rustc_hash::FxHashMap;
+
+/// Interns labels borrowed from the input into dense `u32` ids without copying them.
+#[derive(Debug, Clone, Default)]
+pub struct Interner<'a> {
+    ids: FxHashMap<&'a str, u32>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &'a str) -> u32 {
+        *self.ids.entry(name).or_insert_with(|| {
+            let id = self.names.len() as u32;
+            self.names.push(name);
+            id
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &'a str {
+        self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Copies all names into a single owned arena, keeping their ids.
+    pub fn freeze(&self) -> Symbols {
+        let mut arena = String::with_capacity(self.names.iter().map(|n| n.len()).sum());
+        let mut ends = Vec::with_capacity(self.names.len());
+        for name in &self.names {
+            arena.push_str(name);
+            ends.push(arena.len() as u32);
+        }
+
+        let mut sorted: Vec<u32> = (0..self.names.len() as u32).collect();
+        sorted.sort_unstable_by_key(|&id| self.names[id as usize]);
+        Symbols {
+            arena,
+            ends,
+            sorted,
+        }
+    }
+}
+
+/// Immutable interned names, all stored in one contiguous arena.
+///
+/// Lookup by name is a binary search, so this needs no allocation per name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Symbols {
+    arena: String,
+    ends: Vec<u32>,
+    sorted: Vec<u32>,
+}
+
+impl Symbols {
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.sorted
+            .binary_search_by(|&id| self.name(id).cmp(name))
+            .ok()
+            .map(|idx| self.sorted[idx])
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        let id = id as usize;
+        let start = if id == 0 { 0 } else { self.ends[id - 1] };
+        &self.arena[start as usize..self.ends[id] as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        (0..self.len() as u32).map(|id| (id, self.name(id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_intern() {
+        let input = "you bbb ccc bbb out";
+        let mut interner = Interner::new();
+        let ids: Vec<_> = input.split(' ').map(|n| interner.intern(n)).collect();
+        assert_eq!(ids, [0, 1, 2, 1, 3]);
+        assert_eq!(interner.get("ccc"), Some(2));
+        assert_eq!(interner.name(3), "out");
+
+        let symbols = interner.freeze();
+        assert_eq!(symbols.len(), 4);
+        assert_eq!(symbols.get("bbb"), Some(1));
+        assert_eq!(symbols.get("aaa"), None);
+        assert_eq!(symbols.name(0), "you");
+        assert_eq!(
+            symbols.iter().map(|(_, n)| n).collect::<Vec<_>>(),
+            ["you", "bbb", "ccc", "out"]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::common::{FastInt, fast_parse};
+use num::PrimInt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// An inclusive interval `[start, end]`, it is empty if `start > end`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.start <= x && x <= self.end
+    }
+
+    /// The amount of contained values.
+    ///
+    /// Only panics for an interval spanning the whole range of a 128-bit type.
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        let diff = match (self.start.to_i128(), self.end.to_i128()) {
+            (Some(start), Some(end)) => end.abs_diff(start),
+            _ => self.end.to_u128().unwrap() - self.start.to_u128().unwrap(),
+        };
+        diff.checked_add(1).expect("interval length overflow")
+    }
+
+    pub fn range(&self) -> RangeInclusive<T> {
+        self.start..=self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let i = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!i.is_empty()).then_some(i)
+    }
+
+    /// Checks if `other` starts inside of this interval or right after its end, without overflowing.
+    fn touches_start_of(&self, other: &Self) -> bool {
+        other.start <= self.end || (self.end < T::max_value() && other.start == self.end + T::one())
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseIntervalError<T> {
+    #[error("missing '-' separator")]
+    MissingSeparator,
+    #[error("parse error")]
+    ParseError(#[from] T),
+}
+
+/// Parses `start-end`, both bounds may be negative, e.g. `-5--3`.
+impl<T: PrimInt + FastInt + FromStr> FromStr for Interval<T> {
+    type Err = ParseIntervalError<<T as FromStr>::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let sep = s
+            .get(1..)
+            .and_then(|rest| rest.find('-'))
+            .ok_or(ParseIntervalError::MissingSeparator)?
+            + 1;
+        Ok(Self::new(
+            fast_parse(s[..sep].trim())?,
+            fast_parse(s[sep + 1..].trim())?,
+        ))
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut sorted: Vec<_> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        sorted.sort_unstable_by_key(|i| i.start);
+        Self::from_sorted(sorted)
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges non-empty intervals sorted by their start.
+    fn from_sorted(sorted: impl IntoIterator<Item = Interval<T>>) -> Self {
+        let mut intervals: Vec<Interval<T>> = vec![];
+        for i in sorted {
+            match intervals.last_mut() {
+                Some(last) if last.touches_start_of(&i) => last.end = last.end.max(i.end),
+                _ => intervals.push(i),
+            }
+        }
+        Self { intervals }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The total amount of contained values.
+    pub fn covered_len(&self) -> u128 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    /// Checks if `x` is contained in O(log n).
+    pub fn contains(&self, x: T) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end < x);
+        self.intervals.get(idx).is_some_and(|i| i.start <= x)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // all intervals in first..last touch or overlap the new one
+        let first = self
+            .intervals
+            .partition_point(|i| !i.touches_start_of(&interval));
+        let last = self
+            .intervals
+            .partition_point(|i| interval.touches_start_of(i));
+        let merged = self.intervals[first..last].iter().fold(interval, |a, i| {
+            Interval::new(a.start.min(i.start), a.end.max(i.end))
+        });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_sorted(
+            itertools::merge_join_by(self.iter(), other.iter(), |a, b| a.start <= b.start)
+                .map(|e| e.into_inner()),
+        )
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            intervals.extend(x.intersection(y));
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { intervals }
+    }
+
+    /// All values in `bounds` that are not contained in this set.
+    pub fn complement(&self, bounds: Interval<T>) -> Self {
+        let mut intervals = vec![];
+        if bounds.is_empty() {
+            return Self { intervals };
+        }
+
+        let mut start = bounds.start;
+        for i in self.iter().filter(|i| i.end >= bounds.start) {
+            if i.start > bounds.end {
+                break;
+            }
+            if i.start > start {
+                intervals.push(Interval::new(start, i.start - T::one()));
+            }
+            if i.end >= bounds.end {
+                return Self { intervals };
+            }
+            start = i.end + T::one();
+        }
+
+        intervals.push(Interval::new(start, bounds.end));
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => {
+                self.intersection(&other.complement(Interval::new(first.start, last.end)))
+            }
+            _ => Self::new(),
+        }
+    }
+
+    /// The values between the contained intervals.
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|w| Interval::new(w[0].end + T::one(), w[1].start - T::one()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals
+            .iter()
+            .map(|&(s, e)| Interval::new(s, e))
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let s = set(&[(10, 14), (3, 5), (16, 20), (12, 18), (6, 6), (30, 29)]);
+        assert_eq!(s, set(&[(3, 6), (10, 20)]));
+        assert_eq!(s.covered_len(), 15);
+        assert!(s.contains(6) && s.contains(10) && !s.contains(7) && !s.contains(21));
+        assert_eq!(s.gaps().collect::<Vec<_>>(), [Interval::new(7, 9)]);
+
+        let mut s = s;
+        s.insert(Interval::new(7, 8));
+        assert_eq!(s, set(&[(3, 8), (10, 20)]));
+        s.insert(Interval::new(9, 9));
+        assert_eq!(s, set(&[(3, 20)]));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let s: IntervalSet<u64> = [
+            Interval::new(u64::MAX - 1, u64::MAX),
+            Interval::new(0, u64::MAX - 2),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(s.intervals(), [Interval::new(0, u64::MAX)]);
+        assert_eq!(s.covered_len(), 1 << 64);
+        assert!(s.complement(Interval::new(0, u64::MAX)).is_empty());
+        assert_eq!(Interval::new(i8::MIN, i8::MAX).len(), 256);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 24), (40, 50)]);
+        assert_eq!(a.union(&b), set(&[(0, 30), (40, 50)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 24)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (25, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (40, 50)]));
+        assert_eq!(
+            a.complement(Interval::new(-5, 25)),
+            set(&[(-5, -1), (11, 19)])
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("11-22".parse(), Ok(Interval::new(11u64, 22)));
+        assert_eq!("-5--3".parse(), Ok(Interval::new(-5i64, -3)));
+        assert_eq!(
+            "5".parse::<Interval<u64>>(),
+            Err(ParseIntervalError::MissingSeparator)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::common::{EuclideanSquared, Metric};
+use nalgebra::SVector;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A static k-d tree over integer points with exact distances, measured by [`EuclideanSquared`] unless chosen otherwise.
+///
+/// The tree is stored implicitly: the median of every range is its root, split along `depth % D`.
+#[derive(Debug, Clone)]
+pub struct KdTree<const D: usize, M = EuclideanSquared> {
+    points: Vec<(SVector<i64, D>, usize)>,
+    metric: M,
+}
+
+impl<const D: usize> KdTree<D> {
+    /// Builds the tree, results refer to points by their index in `points`.
+    pub fn new(points: &[SVector<i64, D>]) -> Self {
+        Self::with_metric(points, EuclideanSquared)
+    }
+}
+
+impl<const D: usize, M: Metric> KdTree<D, M> {
+    /// Builds the tree measuring all distances with `metric`.
+    pub fn with_metric(points: &[SVector<i64, D>], metric: M) -> Self {
+        let mut points: Vec<_> = points.iter().copied().zip(0..).collect();
+        Self::build(&mut points, 0);
+        Self { points, metric }
+    }
+
+    fn build(points: &mut [(SVector<i64, D>, usize)], depth: usize) {
+        if points.len() <= 1 {
+            return;
+        }
+
+        let axis = depth % D;
+        let mid = points.len() / 2;
+        points.select_nth_unstable_by_key(mid, |(p, _)| p[axis]);
+        let (left, right) = points.split_at_mut(mid);
+        Self::build(left, depth + 1);
+        Self::build(&mut right[1..], depth + 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Visits all points that might be closer to `query` than the current bound, `None` meaning unbounded.
+    ///
+    /// `f` is called with the index and distance of each visited point and returns the new bound.
+    fn visit(
+        &self,
+        (lo, hi): (usize, usize),
+        depth: usize,
+        query: &SVector<i64, D>,
+        mut bound: Option<u128>,
+        f: &mut impl FnMut(usize, u128) -> Option<u128>,
+    ) -> Option<u128> {
+        if lo >= hi {
+            return bound;
+        }
+
+        let axis = depth % D;
+        let mid = lo + (hi - lo) / 2;
+        let (p, idx) = &self.points[mid];
+        bound = f(*idx, self.metric.dist(p, query));
+
+        let (near, far) = if query[axis] < p[axis] {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        bound = self.visit(near, depth + 1, query, bound, f);
+        let axis_dist = self.metric.axis_dist(query[axis].abs_diff(p[axis]));
+        if bound.is_none_or(|b| axis_dist <= b) {
+            bound = self.visit(far, depth + 1, query, bound, f);
+        }
+        bound
+    }
+
+    /// The `k` points closest to `query` as `(index, distance)`, ordered by distance and then index.
+    pub fn nearest(&self, query: &SVector<i64, D>, k: usize) -> Vec<(usize, u128)> {
+        if k == 0 {
+            return vec![];
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.visit((0, self.len()), 0, query, None, &mut |idx, d| {
+            heap.push((d, idx));
+            if heap.len() > k {
+                heap.pop();
+            }
+            (heap.len() == k).then(|| heap.peek().unwrap().0)
+        });
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|(d, idx)| (idx, d))
+            .collect()
+    }
+
+    /// All points with a distance of at most `radius` to `query` as `(index, distance)`, in no particular order.
+    pub fn within_radius(&self, query: &SVector<i64, D>, radius: u128) -> Vec<(usize, u128)> {
+        let mut result = vec![];
+        self.visit((0, self.len()), 0, query, Some(radius), &mut |idx, d| {
+            if d <= radius {
+                result.push((idx, d));
+            }
+            Some(radius)
+        });
+        result
+    }
+
+    /// Streams all pairs of distinct points as `(a, b, distance)` with `a < b`,
+    /// ordered by distance, then `a`, then `b`.
+    ///
+    /// The neighbors of each point are fetched lazily in growing batches,
+    /// so taking only the closest pairs never materializes all of them.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, D, M> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            query_points: vec![SVector::zeros(); self.len()],
+            neighbors: vec![vec![]; self.len()],
+            cursors: vec![0; self.len()],
+            heap: BinaryHeap::new(),
+        };
+        for &(p, idx) in &self.points {
+            pairs.query_points[idx] = p;
+        }
+        for a in 0..self.len() {
+            pairs.push_next(a);
+        }
+        pairs
+    }
+}
+
+pub struct ClosestPairs<'a, const D: usize, M = EuclideanSquared> {
+    tree: &'a KdTree<D, M>,
+    query_points: Vec<SVector<i64, D>>,
+    neighbors: Vec<Vec<(usize, u128)>>,
+    cursors: Vec<usize>,
+    heap: BinaryHeap<Reverse<(u128, usize, usize)>>,
+}
+
+impl<const D: usize, M: Metric> ClosestPairs<'_, D, M> {
+    const INITIAL_BATCH: usize = 8;
+
+    /// Pushes the next neighbor `b > a` of `a` onto the heap, fetching more neighbors if needed.
+    fn push_next(&mut self, a: usize) {
+        loop {
+            let neighbors = &self.neighbors[a];
+            while let Some(&(b, d)) = neighbors.get(self.cursors[a]) {
+                self.cursors[a] += 1;
+                if b > a {
+                    self.heap.push(Reverse((d, a, b)));
+                    return;
+                }
+            }
+
+            // the point itself is always part of its neighbors
+            let fetched = neighbors.len();
+            if fetched == self.tree.len() {
+                return;
+            }
+            let k = (2 * fetched).max(Self::INITIAL_BATCH).min(self.tree.len());
+            self.neighbors[a] = self.tree.nearest(&self.query_points[a], k);
+        }
+    }
+}
+
+impl<const D: usize, M: Metric> Iterator for ClosestPairs<'_, D, M> {
+    type Item = (usize, usize, u128);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((d, a, b)) = self.heap.pop()?;
+        self.push_next(a);
+        Some((a, b, d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Chebyshev, Manhattan, Vec2i, Vec3i};
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+
+    fn points() -> Vec<Vec3i> {
+        (0..60)
+            .map(|i| Vec3i::new((i * 37) % 23, (i * 11) % 17 - 8, (i * 5) % 13))
+            .collect()
+    }
+
+    #[test]
+    fn test_nearest() {
+        let points = points();
+        let tree = KdTree::new(&points);
+        let query = Vec3i::new(5, 0, 7);
+        let expected = (0..points.len())
+            .map(|i| (i, EuclideanSquared.dist(&points[i], &query)))
+            .sorted_by_key(|&(i, d)| (d, i))
+            .collect_vec();
+        assert_eq!(tree.nearest(&query, 7), expected[..7]);
+        assert_eq!(tree.nearest(&query, 100), expected);
+        assert_eq!(
+            tree.within_radius(&query, 20)
+                .into_iter()
+                .sorted_by_key(|&(i, d)| (d, i))
+                .collect_vec(),
+            expected
+                .iter()
+                .copied()
+                .filter(|&(_, d)| d <= 20)
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_closest_pairs() {
+        let points = points();
+        let expected = (0..points.len())
+            .tuple_combinations()
+            .map(|(a, b)| (a, b, EuclideanSquared.dist(&points[a], &points[b])))
+            .sorted_by_key(|&(a, b, d)| (d, a, b))
+            .collect_vec();
+        assert_eq!(KdTree::new(&points).closest_pairs().collect_vec(), expected);
+
+        let tree = KdTree::new(&[Vec2i::new(0, 0)]);
+        assert_eq!(tree.closest_pairs().next(), None);
+    }
+
+    #[test]
+    fn test_other_metrics() {
+        fn check(metric: impl Metric + Copy) {
+            let points = points();
+            let tree = KdTree::with_metric(&points, metric);
+            let query = Vec3i::new(20, -3, 1);
+            let expected = (0..points.len())
+                .map(|i| (i, metric.dist(&points[i], &query)))
+                .sorted_by_key(|&(i, d)| (d, i))
+                .collect_vec();
+            assert_eq!(tree.nearest(&query, 10), expected[..10]);
+
+            let expected = (0..points.len())
+                .tuple_combinations()
+                .map(|(a, b)| (a, b, metric.dist(&points[a], &points[b])))
+                .sorted_by_key(|&(a, b, d)| (d, a, b))
+                .take(50)
+                .collect_vec();
+            assert_eq!(tree.closest_pairs().take(50).collect_vec(), expected);
+        }
+
+        check(Manhattan);
+        check(Chebyshev);
+    }
+}
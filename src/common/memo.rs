@@ -0,0 +1,253 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::hash::Hash;
+use thiserror::Error;
+
+/// Where a [`Memo`] keeps its computed values.
+pub trait MemoStore<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Hash + Eq, V> MemoStore<K, V> for FxHashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        FxHashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        FxHashMap::insert(self, key, value);
+    }
+}
+
+/// A store for small integer keys, backed by a vector that grows as needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenseStore<V>(Vec<Option<V>>);
+
+impl<V> DenseStore<V> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+}
+
+impl<V> MemoStore<usize, V> for DenseStore<V> {
+    fn get(&self, key: &usize) -> Option<&V> {
+        self.0.get(*key)?.as_ref()
+    }
+
+    fn insert(&mut self, key: usize, value: V) {
+        if key >= self.0.len() {
+            self.0.resize_with(key + 1, || None);
+        }
+        self.0[key] = Some(value);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MemoStats {
+    /// Lookups answered from the store.
+    pub hits: u64,
+    /// Values that had to be computed.
+    pub misses: u64,
+    /// Calls of the function that stopped because a dependency was not known yet.
+    pub retries: u64,
+}
+
+/// The values a [`Memo`] function depends on.
+///
+/// [`Deps::get`] returns `None` for values that were not computed yet, the function should then
+/// return `None` as well and is called again once they are known. All missing values of one call
+/// are computed before that, so look up every dependency before returning.
+pub struct Deps<'a, K, V> {
+    store: &'a dyn MemoStore<K, V>,
+    missing: &'a mut Vec<K>,
+    hits: &'a mut u64,
+}
+
+impl<'a, K: Clone, V> Deps<'a, K, V> {
+    pub fn get(&mut self, key: &K) -> Option<&'a V> {
+        let value = self.store.get(key);
+        match value {
+            Some(_) => *self.hits += 1,
+            None => self.missing.push(key.clone()),
+        }
+        value
+    }
+}
+
+/// Memoizes a recursive function, evaluated with an explicit work stack so deep recursions can't
+/// overflow the call stack.
+///
+/// The function gets its dependencies from [`Deps`] and should look up all of them before
+/// applying `?`, stopping at the first missing one calls a key with `d` dependencies `d` times:
+/// `Memo::new(|&n, memo| if n < 2 { Some(n) } else { let (a, b) = (memo.get(&(n - 1)), memo.get(&(n - 2))); Some(a? + b?) })`.
+/// The memoized function depends on the key while computing it, directly or indirectly.
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[error("cyclic dependency in memoized function")]
+pub struct CyclicDependency<K>(pub K);
+
+pub struct Memo<K, V, S, F> {
+    store: S,
+    f: F,
+    stats: MemoStats,
+    _marker: std::marker::PhantomData<fn(K) -> V>,
+}
+
+impl<K, V, F> Memo<K, V, FxHashMap<K, V>, F>
+where
+    K: Hash + Eq + Clone,
+    F: FnMut(&K, &mut Deps<'_, K, V>) -> Option<V>,
+{
+    pub fn new(f: F) -> Self {
+        Self::with_store(FxHashMap::default(), f)
+    }
+}
+
+impl<V, F> Memo<usize, V, DenseStore<V>, F>
+where
+    F: FnMut(&usize, &mut Deps<'_, usize, V>) -> Option<V>,
+{
+    /// A memo for keys in `0..capacity`, larger keys are supported but grow the store.
+    pub fn dense(capacity: usize, f: F) -> Self {
+        Self::with_store(DenseStore::with_capacity(capacity), f)
+    }
+}
+
+impl<K, V, S, F> Memo<K, V, S, F>
+where
+    K: Hash + Eq + Clone,
+    S: MemoStore<K, V>,
+    F: FnMut(&K, &mut Deps<'_, K, V>) -> Option<V>,
+{
+    pub fn with_store(store: S, f: F) -> Self {
+        Self {
+            store,
+            f,
+            stats: MemoStats::default(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The value for `key`, computing it and everything it depends on if necessary.
+    ///
+    /// Panics if the function depends on itself, directly or indirectly.
+    pub fn get(&mut self, key: K) -> &V {
+        match self.try_get(key) {
+            Ok(value) => value,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Same as [`Memo::get`], but reports a key that depends on itself instead of panicking.
+    ///
+    /// Values computed before the cycle was found are kept.
+    pub fn try_get(&mut self, key: K) -> Result<&V, CyclicDependency<K>> {
+        if self.store.get(&key).is_some() {
+            self.stats.hits += 1;
+            return Ok(self.store.get(&key).unwrap());
+        }
+
+        let mut stack = vec![key.clone()];
+        // keys that are waiting for dependencies, these are the ancestors of the top of the stack
+        let mut expanded = FxHashSet::default();
+        let mut missing = vec![];
+        while let Some(k) = stack.last() {
+            if self.store.get(k).is_some() {
+                stack.pop();
+                continue;
+            }
+
+            let mut deps = Deps {
+                store: &self.store,
+                missing: &mut missing,
+                hits: &mut self.stats.hits,
+            };
+            match (self.f)(k, &mut deps) {
+                Some(value) => {
+                    self.stats.misses += 1;
+                    let k = stack.pop().unwrap();
+                    expanded.remove(&k);
+                    self.store.insert(k, value);
+                }
+                None => {
+                    assert!(
+                        !missing.is_empty(),
+                        "memoized function returned None without a missing dependency"
+                    );
+                    self.stats.retries += 1;
+                    expanded.insert(k.clone());
+                    for m in missing.drain(..) {
+                        if expanded.contains(&m) {
+                            return Err(CyclicDependency(m));
+                        }
+                        stack.push(m);
+                    }
+                }
+            }
+        }
+        Ok(self.store.get(&key).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fibonacci() {
+        let mut fib = Memo::new(|&n: &u64, memo| {
+            if n < 2 {
+                Some(n as u128)
+            } else {
+                let (a, b) = (memo.get(&(n - 1)), memo.get(&(n - 2)));
+                Some(a? + b?)
+            }
+        });
+        assert_eq!(*fib.get(90), 2880067194370816120);
+        let stats = fib.stats();
+        assert_eq!(stats.misses, 91);
+        assert_eq!(*fib.get(50), 12586269025);
+        assert_eq!(fib.stats().hits, stats.hits + 1);
+    }
+
+    #[test]
+    fn test_deep_dense() {
+        // far deeper than the call stack would allow
+        let n = 1_000_000;
+        let mut sum = Memo::dense(n + 1, |&i, memo| match i {
+            0 => Some(0u64),
+            _ => Some(memo.get(&(i - 1))? + i as u64),
+        });
+        assert_eq!(*sum.get(n), (n as u64) * (n as u64 + 1) / 2);
+        assert_eq!(sum.stats().misses, n as u64 + 1);
+        assert_eq!(sum.store().get(&10), Some(&55));
+    }
+
+    #[test]
+    #[should_panic(expected = "cyclic dependency")]
+    fn test_cycle() {
+        let mut m = Memo::<_, u32, _, _>::new(|&i: &u32, memo| memo.get(&((i + 1) % 3)).copied());
+        m.get(0);
+    }
+
+    #[test]
+    fn test_try_get_cycle() {
+        // 3 -> 4 -> 5 -> 3, everything below 3 ends in 0
+        let mut m = Memo::dense(6, |&i, memo| match i {
+            0 => Some(0u32),
+            1..3 => memo.get(&(i - 1)).map(|v| v + 1),
+            _ => memo.get(&(3 + (i - 2) % 3)).copied(),
+        });
+        assert_eq!(m.try_get(2), Ok(&2));
+        assert_eq!(m.try_get(4), Err(CyclicDependency(4)));
+        assert_eq!(m.try_get(1), Ok(&1));
+    }
+}
@@ -0,0 +1,117 @@
+use nalgebra::SVector;
+
+/// A distance between integer points, computed exactly with `abs_diff` and saturating instead of overflowing.
+///
+/// Distances only need to preserve the order of the real distances, e.g. [`EuclideanSquared`] skips the root.
+pub trait Metric {
+    fn dist<const D: usize>(&self, a: &SVector<i64, D>, b: &SVector<i64, D>) -> u128;
+
+    /// The distance of two points that differ by `delta` along a single axis.
+    ///
+    /// This must be a lower bound for the distance of any two points that differ by at least `delta` along some axis,
+    /// search structures rely on it for pruning.
+    fn axis_dist(&self, delta: u64) -> u128;
+
+    /// The distance from `v` to the origin.
+    fn norm<const D: usize>(&self, v: &SVector<i64, D>) -> u128 {
+        self.dist(v, &SVector::zeros())
+    }
+}
+
+fn axis_deltas<'a, const D: usize>(
+    a: &'a SVector<i64, D>,
+    b: &'a SVector<i64, D>,
+) -> impl Iterator<Item = u128> + 'a {
+    a.iter().zip(b.iter()).map(|(&a, &b)| a.abs_diff(b) as u128)
+}
+
+/// The L1 or taxicab distance.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Manhattan;
+
+impl Metric for Manhattan {
+    fn dist<const D: usize>(&self, a: &SVector<i64, D>, b: &SVector<i64, D>) -> u128 {
+        axis_deltas(a, b).fold(0, u128::saturating_add)
+    }
+
+    fn axis_dist(&self, delta: u64) -> u128 {
+        delta as u128
+    }
+}
+
+/// The squared euclidean distance, which stays exact on integers.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct EuclideanSquared;
+
+impl Metric for EuclideanSquared {
+    fn dist<const D: usize>(&self, a: &SVector<i64, D>, b: &SVector<i64, D>) -> u128 {
+        axis_deltas(a, b)
+            .map(|d| d.saturating_mul(d))
+            .fold(0, u128::saturating_add)
+    }
+
+    fn axis_dist(&self, delta: u64) -> u128 {
+        (delta as u128).pow(2)
+    }
+}
+
+/// The L∞ distance, the number of king moves between two points.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Chebyshev;
+
+impl Metric for Chebyshev {
+    fn dist<const D: usize>(&self, a: &SVector<i64, D>, b: &SVector<i64, D>) -> u128 {
+        axis_deltas(a, b).max().unwrap_or(0)
+    }
+
+    fn axis_dist(&self, delta: u64) -> u128 {
+        delta as u128
+    }
+}
+
+/// The Lp distance raised to the power `p`, so `Lp(1)` matches [`Manhattan`] and `Lp(2)` matches [`EuclideanSquared`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Lp(pub u32);
+
+impl Metric for Lp {
+    fn dist<const D: usize>(&self, a: &SVector<i64, D>, b: &SVector<i64, D>) -> u128 {
+        axis_deltas(a, b)
+            .map(|d| d.saturating_pow(self.0))
+            .fold(0, u128::saturating_add)
+    }
+
+    fn axis_dist(&self, delta: u64) -> u128 {
+        (delta as u128).saturating_pow(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Vec2i, Vec3i};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_metrics() {
+        let (a, b) = (Vec3i::new(1, -2, 3), Vec3i::new(-3, 4, 3));
+        assert_eq!(Manhattan.dist(&a, &b), 10);
+        assert_eq!(EuclideanSquared.dist(&a, &b), 52);
+        assert_eq!(Chebyshev.dist(&a, &b), 6);
+        assert_eq!(Lp(1).dist(&a, &b), 10);
+        assert_eq!(Lp(2).dist(&a, &b), 52);
+        assert_eq!(Lp(3).dist(&a, &b), 64 + 216);
+        assert_eq!(Manhattan.norm(&a), 6);
+    }
+
+    #[test]
+    fn test_metrics_extreme() {
+        let (a, b) = (
+            Vec2i::new(i64::MIN, i64::MIN),
+            Vec2i::new(i64::MAX, i64::MAX),
+        );
+        assert_eq!(Manhattan.dist(&a, &b), 2 * u64::MAX as u128);
+        assert_eq!(Chebyshev.dist(&a, &b), u64::MAX as u128);
+        assert_eq!(EuclideanSquared.dist(&a, &b), u128::MAX);
+        assert_eq!(Lp(5).dist(&a, &b), u128::MAX);
+    }
+}
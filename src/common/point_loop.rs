@@ -0,0 +1,156 @@
+use crate::common::{Direction, Orientation, Vec2i, orientation};
+use itertools::Itertools;
+use num::integer::gcd;
+
+/// Twice the signed area enclosed by the loop through `vertices`, the closing edge back to the first vertex is implicit.
+///
+/// The area is positive for clockwise loops, as seen with the y-axis pointing down like [`Direction`].
+pub fn shoelace_area2(vertices: &[Vec2i]) -> i128 {
+    vertices
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+        .sum()
+}
+
+/// The number of lattice points on the loop through `vertices`.
+pub fn boundary_points(vertices: &[Vec2i]) -> u128 {
+    vertices
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| gcd(a.x.abs_diff(b.x), a.y.abs_diff(b.y)) as u128)
+        .sum()
+}
+
+/// The number of lattice points strictly inside the simple loop through `vertices`, using Pick's theorem.
+///
+/// Degenerate loops, e.g. with all vertices on a line, enclose no points.
+pub fn interior_points(vertices: &[Vec2i]) -> u128 {
+    if vertices.len() < 3 {
+        return 0;
+    }
+    // A = I + B / 2 - 1, solving for I goes negative for loops without area
+    (shoelace_area2(vertices).unsigned_abs() + 2).saturating_sub(boundary_points(vertices)) / 2
+}
+
+/// The convex hull of `points` in clockwise order, starting at the smallest point by `(x, y)`.
+///
+/// Collinear points on the hull are dropped, the hull of fewer than three distinct points is just those points.
+pub fn convex_hull(points: &[Vec2i]) -> Vec<Vec2i> {
+    let points: Vec<_> = points
+        .iter()
+        .copied()
+        .sorted_unstable_by_key(|p| (p.x, p.y))
+        .dedup()
+        .collect();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // monotone chain: the upper half from left to right, then the lower half back, only keeping clockwise turns
+    fn push(hull: &mut Vec<Vec2i>, keep: usize, p: Vec2i) {
+        while hull.len() > keep
+            && orientation(hull[hull.len() - 2], hull[hull.len() - 1], p) != Orientation::Clockwise
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    let mut hull = Vec::with_capacity(points.len() + 1);
+    for &p in &points {
+        push(&mut hull, 1, p);
+    }
+    let keep = hull.len();
+    for &p in points.iter().rev().skip(1) {
+        push(&mut hull, keep, p);
+    }
+    // the lower half ends at the first point again
+    hull.pop();
+    hull
+}
+
+/// The vertices visited when walking from `start` by each `(direction, distance)` step.
+///
+/// If the walk returns to `start`, the last vertex is dropped, so the result is a loop with an implicit closing edge.
+pub fn loop_from_steps(
+    start: Vec2i,
+    steps: impl IntoIterator<Item = (Direction, i64)>,
+) -> Vec<Vec2i> {
+    let mut vertices = vec![start];
+    for (direction, distance) in steps {
+        let next = direction.offset_with_amount(vertices.last().unwrap(), distance);
+        vertices.push(next);
+    }
+    if vertices.len() > 1 && vertices.last() == Some(&start) {
+        vertices.pop();
+    }
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_pick() {
+        // a 4x3 rectangle, walked clockwise with y pointing down
+        let steps = [
+            (Direction::East, 4),
+            (Direction::South, 3),
+            (Direction::West, 4),
+            (Direction::North, 3),
+        ];
+        let vertices = loop_from_steps(Vec2i::new(1, 1), steps);
+        assert_eq!(
+            vertices,
+            [
+                Vec2i::new(1, 1),
+                Vec2i::new(5, 1),
+                Vec2i::new(5, 4),
+                Vec2i::new(1, 4)
+            ]
+        );
+        assert_eq!(shoelace_area2(&vertices), 24);
+        assert_eq!(boundary_points(&vertices), 14);
+        assert_eq!(interior_points(&vertices), 3 * 2);
+
+        let reversed: Vec<_> = vertices.iter().rev().copied().collect();
+        assert_eq!(shoelace_area2(&reversed), -24);
+
+        let triangle = [Vec2i::new(0, 0), Vec2i::new(4, 2), Vec2i::new(0, 6)];
+        assert_eq!(shoelace_area2(&triangle), 24);
+        assert_eq!(boundary_points(&triangle), 2 + 4 + 6);
+        assert_eq!(interior_points(&triangle), 7);
+
+        let line = [Vec2i::new(0, 0), Vec2i::new(5, 0), Vec2i::new(2, 0)];
+        assert_eq!(boundary_points(&line), 10);
+        assert_eq!(interior_points(&line), 0);
+        assert_eq!(interior_points(&[Vec2i::new(1, 1)]), 0);
+        assert_eq!(interior_points(&[]), 0);
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points: Vec<_> = (0..5)
+            .flat_map(|x| (0..4).map(move |y| Vec2i::new(x, y)))
+            .chain([Vec2i::new(2, -2), Vec2i::new(2, 1)])
+            .collect();
+        assert_eq!(
+            convex_hull(&points),
+            [
+                Vec2i::new(0, 0),
+                Vec2i::new(2, -2),
+                Vec2i::new(4, 0),
+                Vec2i::new(4, 3),
+                Vec2i::new(0, 3)
+            ]
+        );
+        assert_eq!(
+            convex_hull(&[Vec2i::new(0, 0), Vec2i::new(1, 1), Vec2i::new(2, 2)]),
+            [Vec2i::new(0, 0), Vec2i::new(2, 2)]
+        );
+        assert_eq!(convex_hull(&[Vec2i::new(3, 3); 3]), [Vec2i::new(3, 3)]);
+    }
+}
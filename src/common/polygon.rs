@@ -0,0 +1,240 @@
+use crate::common::{Aabb2, Vec2i, shoelace_area2};
+use itertools::Itertools;
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// Orientation of a vertex loop, as seen with the y-axis pointing down like [`crate::common::Direction`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Collinear,
+}
+
+impl Orientation {
+    /// The orientation of a signed area or cross product compared to zero, positive meaning clockwise.
+    pub fn from_sign(sign: Ordering) -> Self {
+        match sign {
+            Ordering::Greater => Self::Clockwise,
+            Ordering::Less => Self::CounterClockwise,
+            Ordering::Equal => Self::Collinear,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PolygonError {
+    #[error("a polygon needs at least 4 vertices, got {0}")]
+    TooFewVertices(usize),
+    #[error("the edge starting at vertex {0} is not axis-aligned")]
+    NotAxisAligned(usize),
+    #[error("the edge starting at vertex {0} has zero length")]
+    ZeroLength(usize),
+}
+
+/// A closed polygon with only horizontal and vertical edges.
+///
+/// All containment tests are exact and treat the boundary as part of the polygon.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Vec2i>,
+}
+
+impl RectilinearPolygon {
+    /// Builds the polygon from its vertex loop, the last vertex connects back to the first.
+    ///
+    /// Repeating the first vertex at the end is allowed.
+    pub fn new(mut vertices: Vec<Vec2i>) -> Result<Self, PolygonError> {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        if vertices.len() < 4 {
+            return Err(PolygonError::TooFewVertices(vertices.len()));
+        }
+
+        for (i, (a, b)) in vertices.iter().circular_tuple_windows().enumerate() {
+            if a == b {
+                return Err(PolygonError::ZeroLength(i));
+            } else if a.x != b.x && a.y != b.y {
+                return Err(PolygonError::NotAxisAligned(i));
+            }
+        }
+
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Vec2i] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2i, Vec2i)> + '_ {
+        self.vertices.iter().copied().circular_tuple_windows()
+    }
+
+    /// Twice the signed area, positive for clockwise loops.
+    fn signed_area2(&self) -> i128 {
+        shoelace_area2(&self.vertices)
+    }
+
+    /// The enclosed area, measured between the vertex coordinates.
+    pub fn area(&self) -> u128 {
+        self.signed_area2().unsigned_abs() / 2
+    }
+
+    pub fn perimeter(&self) -> u64 {
+        self.edges()
+            .map(|(a, b)| a.x.abs_diff(b.x) + a.y.abs_diff(b.y))
+            .sum()
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        Orientation::from_sign(self.signed_area2().cmp(&0))
+    }
+
+    pub fn classify(&self, p: Vec2i) -> Containment {
+        self.classify_scaled(p, 1)
+    }
+
+    /// Classifies `p` against the polygon scaled by `scale`, which allows testing points at fractional coordinates.
+    fn classify_scaled(&self, p: Vec2i, scale: i64) -> Containment {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (a, b) = (a * scale, b * scale);
+            if Aabb2::from_corners(a, b).contains(&p) {
+                return Containment::Boundary;
+            }
+
+            // cast a ray in positive x direction, only vertical edges can cross it
+            if a.x == b.x && a.x > p.x && a.y.min(b.y) <= p.y && p.y < a.y.max(b.y) {
+                inside = !inside;
+            }
+        }
+
+        if inside {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+
+    /// Checks if the axis-aligned rectangle with the corners `a` and `b` lies completely inside the polygon.
+    pub fn contains_rect(&self, a: Vec2i, b: Vec2i) -> bool {
+        let rect = Aabb2::from_corners(a, b);
+        let (min, max) = (rect.min, rect.max);
+
+        // no edge may cross the open inside of the rectangle,
+        // which means the inside is either completely inside or completely outside the polygon
+        let crosses_inside = self
+            .edges()
+            .any(|(start, end)| Aabb2::from_corners(start, end).overlaps_interior(&rect));
+        if crosses_inside {
+            return false;
+        }
+
+        if min.x < max.x && min.y < max.y {
+            return self.classify_scaled(min + max, 2) == Containment::Inside;
+        }
+
+        // the rectangle is a line segment (or a single point): the classification can only change at vertex coordinates,
+        // so check every vertex coordinate on the segment and every point between them
+        let axis = if min.x < max.x { 0 } else { 1 };
+        let breaks: Vec<_> = self
+            .vertices
+            .iter()
+            .map(|v| v[axis])
+            .filter(|&c| min[axis] < c && c < max[axis])
+            .chain([min[axis], max[axis]])
+            .sorted_unstable()
+            .dedup()
+            .collect();
+        let at = |c2: i64| {
+            let mut p = min * 2;
+            p[axis] = c2;
+            self.classify_scaled(p, 2) != Containment::Outside
+        };
+        breaks.iter().all(|&c| at(2 * c)) && breaks.iter().tuple_windows().all(|(&c, &d)| at(c + d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// a U-shape, opening upward
+    fn u_shape() -> RectilinearPolygon {
+        RectilinearPolygon::new(vec![
+            Vec2i::new(0, 0),
+            Vec2i::new(2, 0),
+            Vec2i::new(2, 4),
+            Vec2i::new(4, 4),
+            Vec2i::new(4, 0),
+            Vec2i::new(6, 0),
+            Vec2i::new(6, 6),
+            Vec2i::new(0, 6),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            RectilinearPolygon::new(vec![Vec2i::new(0, 0), Vec2i::new(1, 0), Vec2i::new(1, 1)]),
+            Err(PolygonError::TooFewVertices(3))
+        );
+        assert_eq!(
+            RectilinearPolygon::new(vec![
+                Vec2i::new(0, 0),
+                Vec2i::new(1, 0),
+                Vec2i::new(1, 1),
+                Vec2i::new(0, 2),
+            ]),
+            Err(PolygonError::NotAxisAligned(2))
+        );
+    }
+
+    #[test]
+    fn test_measures() {
+        let p = u_shape();
+        assert_eq!(p.area(), 28);
+        assert_eq!(p.perimeter(), 32);
+        assert_eq!(p.orientation(), Orientation::Clockwise);
+        let mut reversed = p.vertices().to_vec();
+        reversed.reverse();
+        assert_eq!(
+            RectilinearPolygon::new(reversed).unwrap().orientation(),
+            Orientation::CounterClockwise
+        );
+    }
+
+    #[test]
+    fn test_classify() {
+        let p = u_shape();
+        assert_eq!(p.classify(Vec2i::new(1, 1)), Containment::Inside);
+        assert_eq!(p.classify(Vec2i::new(3, 1)), Containment::Outside);
+        assert_eq!(p.classify(Vec2i::new(3, 4)), Containment::Boundary);
+        assert_eq!(p.classify(Vec2i::new(0, 3)), Containment::Boundary);
+        assert_eq!(p.classify(Vec2i::new(3, 5)), Containment::Inside);
+        assert_eq!(p.classify(Vec2i::new(7, 0)), Containment::Outside);
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        let p = u_shape();
+        assert!(p.contains_rect(Vec2i::new(0, 0), Vec2i::new(2, 6)));
+        assert!(p.contains_rect(Vec2i::new(0, 6), Vec2i::new(6, 4)));
+        assert!(!p.contains_rect(Vec2i::new(0, 0), Vec2i::new(6, 2)));
+        // the corners touch the polygon, but the inside is the notch
+        assert!(!p.contains_rect(Vec2i::new(2, 0), Vec2i::new(4, 4)));
+        assert!(!p.contains_rect(Vec2i::new(2, 0), Vec2i::new(4, 0)));
+        assert!(p.contains_rect(Vec2i::new(2, 4), Vec2i::new(4, 4)));
+        assert!(p.contains_rect(Vec2i::new(1, 5), Vec2i::new(1, 5)));
+    }
+}
@@ -1,5 +1,6 @@
-use crate::common::parse_lines;
+use crate::common::{ReadError, fast_parse, parse_lines_from, parse_lines_strict};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io::BufRead;
 use std::str::FromStr;
 
 const WHEEL_SIZE: u32 = 100;
@@ -16,53 +17,93 @@ impl FromStr for Rotation {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let neg = s.starts_with('L');
         let rest = s.strip_prefix(['L', 'R']).ok_or(())?;
-        let res: i32 = rest.parse().map_err(|_| ())?;
+        let res: i32 = fast_parse(rest).map_err(|_| ())?;
         Ok(Self(if neg { -res } else { res }))
     }
 }
 
-#[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Vec<Rotation> {
-    parse_lines(input).unwrap()
+/// The state of the dial, updated one rotation at a time.
+#[derive(Debug, Copy, Clone)]
+pub struct Dial {
+    pos: u32,
+    stopped_at_zero: usize,
+    passed_zero: usize,
 }
 
-#[aoc(day1, part1)]
-pub fn part1(input: &[Rotation]) -> usize {
-    let mut amount = 0;
-    let mut n = STARTING_POS;
-    for r in input {
-        n = (n as i32).wrapping_add(r.0).rem_euclid(WHEEL_SIZE as i32) as u32;
-        if n == 0 {
-            amount += 1;
+impl Default for Dial {
+    fn default() -> Self {
+        Self {
+            pos: STARTING_POS,
+            stopped_at_zero: 0,
+            passed_zero: 0,
         }
     }
-
-    amount
 }
 
-#[aoc(day1, part2)]
-pub fn part2(input: &[Rotation]) -> usize {
-    let mut amount = 0;
-    let mut n = STARTING_POS;
-    for r in input {
-        if r.0 == 0 {
-            continue;
-        }
-
-        if n > 0 {
+impl Dial {
+    pub fn rotate(&mut self, r: Rotation) {
+        // a zero rotation never passes zero, but still stops on it
+        if self.pos > 0 && r.0 != 0 {
             let abs = r.0.unsigned_abs();
-            amount += (abs / WHEEL_SIZE) as usize;
+            self.passed_zero += (abs / WHEEL_SIZE) as usize;
             let rest = abs % WHEEL_SIZE;
-            let diff = if r.0 >= 0 { WHEEL_SIZE - n } else { n };
+            let diff = if r.0 >= 0 {
+                WHEEL_SIZE - self.pos
+            } else {
+                self.pos
+            };
             if rest >= diff {
-                amount += 1;
+                self.passed_zero += 1;
             }
         }
 
-        n = (n as i32).wrapping_add(r.0).rem_euclid(WHEEL_SIZE as i32) as u32;
+        self.pos = (self.pos as i32)
+            .wrapping_add(r.0)
+            .rem_euclid(WHEEL_SIZE as i32) as u32;
+        if self.pos == 0 {
+            self.stopped_at_zero += 1;
+        }
     }
+}
 
-    amount
+#[aoc_generator(day1)]
+pub fn input_generator(input: &str) -> Vec<Rotation> {
+    parse_lines_strict(input).unwrap()
+}
+
+fn turn(rotations: impl IntoIterator<Item = Rotation>) -> Dial {
+    let mut dial = Dial::default();
+    rotations.into_iter().for_each(|r| dial.rotate(r));
+    dial
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+fn turn_streaming<R: BufRead>(reader: R) -> Result<Dial, ReadError<()>> {
+    let mut dial = Dial::default();
+    for r in parse_lines_from(reader) {
+        dial.rotate(r?);
+    }
+    Ok(dial)
+}
+
+#[aoc(day1, part1)]
+pub fn part1(input: &[Rotation]) -> usize {
+    turn(input.iter().copied()).stopped_at_zero
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn part1_streaming<R: BufRead>(reader: R) -> Result<usize, ReadError<()>> {
+    Ok(turn_streaming(reader)?.stopped_at_zero)
+}
+
+#[aoc(day1, part2)]
+pub fn part2(input: &[Rotation]) -> usize {
+    turn(input.iter().copied()).passed_zero
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<usize, ReadError<()>> {
+    Ok(turn_streaming(reader)?.passed_zero)
 }
 
 #[cfg(test)]
@@ -90,4 +131,17 @@ L82"#;
     fn test_part2() {
         assert_eq!(part2(&input_generator(INPUT)), 6);
     }
+
+    #[test]
+    fn test_zero_rotation() {
+        let input = input_generator("L50\nR0");
+        assert_eq!(part1(&input), 2);
+        assert_eq!(part2(&input), 1);
+    }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(part1_streaming(INPUT.as_bytes()).unwrap(), 3);
+        assert_eq!(part2_streaming(INPUT.as_bytes()).unwrap(), 6);
+    }
 }
@@ -1,74 +1,75 @@
-use crate::common::parse_split;
+use crate::common::{Interval, digit_count, divisors, mobius, parse_split_strict, repunit};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::str::FromStr;
-
-#[derive(Debug, Copy, Clone)]
-pub struct Range(pub u64, pub u64);
-
-impl FromStr for Range {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once('-').ok_or(())?;
-        Ok(Self(
-            start.parse().map_err(|_| ())?,
-            end.parse().map_err(|_| ())?,
-        ))
-    }
-}
+use std::sync::LazyLock;
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<Range> {
-    parse_split(input, ',').unwrap()
+pub fn input_generator(input: &str) -> Vec<Interval<u64>> {
+    parse_split_strict(input, ',').unwrap()
 }
 
-fn is_repeated_twice(n: u64) -> bool {
-    let s = n.to_string();
-    if !s.len().is_multiple_of(2) {
-        return false;
+/// The divisors of every possible digit count of a `u64`, indexed by the digit count.
+static DIVISORS: LazyLock<Vec<Vec<u64>>> =
+    LazyLock::new(|| (0..=digit_count(u64::MAX) as u64).map(divisors).collect());
+
+/// The sum of the numbers in `range` made of a `block` digit number repeated `count` times.
+///
+/// Those numbers are exactly the multiples of the matching repunit by a `block` digit factor, so
+/// this is the sum of an arithmetic series.
+fn sum_repeated(range: &Interval<u64>, block: u32, count: u32) -> u128 {
+    let Some(r) = repunit(block, count) else {
+        return 0;
+    };
+    let lo = range.start.div_ceil(r).max(10u64.pow(block - 1)) as u128;
+    let hi = (range.end / r).min(10u64.pow(block) - 1) as u128;
+    if lo > hi {
+        return 0;
     }
+    r as u128 * (lo + hi) * (hi - lo + 1) / 2
+}
 
-    let (prefix, suffix) = s.split_at(s.len() / 2);
-    prefix == suffix
+/// The digit counts of the numbers in `range`.
+fn lengths(range: &Interval<u64>) -> impl Iterator<Item = u32> {
+    digit_count(range.start)..=digit_count(range.end)
 }
 
-#[aoc(day2, part1)]
-pub fn part1(input: &[Range]) -> u64 {
-    input
-        .iter()
-        .flat_map(|r| r.0..=r.1)
-        .filter(|&n| is_repeated_twice(n))
+fn sum_repeated_twice(range: &Interval<u64>) -> u128 {
+    lengths(range)
+        .filter(|len| len.is_multiple_of(2))
+        .map(|len| sum_repeated(range, len / 2, 2))
         .sum()
 }
 
-fn is_repeated_at_least_twice(n: u64) -> bool {
-    let s = n.to_string();
-    let len = s.len();
-    for part_len in 1..=(len / 2) {
-        if !len.is_multiple_of(part_len) {
-            continue;
-        }
-
-        let (prefix, mut rest) = s.split_at(part_len);
-        while let Some(rest_) = rest.strip_prefix(prefix) {
-            if rest_.is_empty() {
-                return true;
-            }
-
-            rest = rest_;
-        }
-    }
+#[aoc(day2, part1)]
+pub fn part1(input: &[Interval<u64>]) -> u64 {
+    let sum: u128 = input.iter().map(sum_repeated_twice).sum();
+    sum.try_into().expect("the sum fits in a u64")
+}
 
-    false
+/// The sum of the numbers in `range` made of a block repeated at least twice.
+///
+/// A number of `len` digits made of a `block` digit number repeated is also made of any block
+/// length that `block` divides, so the union over the proper divisors of `len` is counted by
+/// Möbius inclusion–exclusion: the numbers whose shortest block is `len` are
+/// `Σ_{d | len} μ(len / d) S(d)`, where `S(d)` sums the numbers made of `d` digit blocks, and the
+/// repeated ones are all the others.
+fn sum_repeated_at_least_twice(range: &Interval<u64>) -> i128 {
+    lengths(range)
+        .flat_map(|len| {
+            DIVISORS[len as usize]
+                .iter()
+                .map(|&d| d as u32)
+                .filter(move |&d| d < len)
+                .map(move |d| {
+                    -i128::from(mobius((len / d) as u64)) * sum_repeated(range, d, len / d) as i128
+                })
+        })
+        .sum()
 }
 
 #[aoc(day2, part2)]
-pub fn part2(input: &[Range]) -> u64 {
-    input
-        .iter()
-        .flat_map(|r| r.0..=r.1)
-        .filter(|&n| is_repeated_at_least_twice(n))
-        .sum()
+pub fn part2(input: &[Interval<u64>]) -> u64 {
+    let sum: i128 = input.iter().map(sum_repeated_at_least_twice).sum();
+    sum.try_into().expect("the sum fits in a u64")
 }
 
 #[cfg(test)]
@@ -87,4 +88,21 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(&input_generator(INPUT)), 4174379265);
     }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let repeated = |n: u64, count: usize| {
+            let s = n.to_string();
+            s.len().is_multiple_of(count) && s == s[..s.len() / count].repeat(count)
+        };
+        for (start, end) in [(1, 99_999), (123_456, 234_567), (999_990, 1_000_100)] {
+            let input = [Interval { start, end }];
+            let twice = (start..=end).filter(|&n| repeated(n, 2)).sum::<u64>();
+            let at_least_twice = (start..=end)
+                .filter(|&n| (2..=20).any(|count| repeated(n, count)))
+                .sum::<u64>();
+            assert_eq!(part1(&input), twice);
+            assert_eq!(part2(&input), at_least_twice);
+        }
+    }
 }
@@ -1,5 +1,6 @@
-use crate::common::parse_lines;
+use crate::common::{Memo, ReadError, parse_digit_run, parse_lines_from, parse_lines_strict};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::io::BufRead;
 use std::str::FromStr;
 
 #[derive(Debug, Clone)]
@@ -9,56 +10,39 @@ impl FromStr for BatteryBank {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(
-            s.chars().map(|c| c.to_digit(10).unwrap() as u8).collect(),
-        ))
+        Ok(Self(parse_digit_run(s.as_bytes()).map_err(|_| ())?))
     }
 }
 
 #[aoc_generator(day3)]
 pub fn input_generator(input: &str) -> Vec<BatteryBank> {
-    parse_lines(input).unwrap()
-}
-
-/// returns the first found maximum in the given array, unlike the rust stdlib max functions which return the last
-///
-/// also includes an optimization to early exit if a global maximum was reached
-fn first_max<T: Ord + Copy>(elements: &[T], max_value: T) -> (usize, T) {
-    debug_assert!(elements.iter().all(|&x| x <= max_value));
-    if elements.is_empty() {
-        panic!("empty input");
-    }
-
-    if elements[0] == max_value {
-        return (0, elements[0]);
-    }
-
-    let mut max = 0;
-    for (idx, &x) in elements.iter().enumerate().skip(1) {
-        if x == max_value {
-            return (idx, x);
-        }
-        if x > elements[max] {
-            max = idx;
-        }
-    }
-
-    (max, elements[max])
+    parse_lines_strict(input).unwrap()
 }
 
+/// The largest number formed by `batteries` digits of the bank, keeping their order.
 pub fn max_joltage(bank: &BatteryBank, batteries: u32) -> u64 {
-    assert!(batteries as usize <= bank.0.len());
-
-    let mut result = 0;
-    let mut start = 0;
-    for bat_idx in (0..batteries).rev() {
-        let pow10 = 10u64.pow(bat_idx);
-        let (idx, max) = first_max(&bank.0[start..(bank.0.len() - bat_idx as usize)], 9);
-        start += idx + 1;
-        result += pow10 * max as u64;
-    }
-
-    result
+    let (len, batteries) = (bank.0.len(), batteries as usize);
+    assert!(batteries <= len);
+
+    // the best joltage of `count` batteries chosen from `start..`, either taking the one at
+    // `start` or skipping it if enough batteries are left
+    let key = |start: usize, count: usize| start * (batteries + 1) + count;
+    let mut best = Memo::dense((len + 1) * (batteries + 1), |&k, memo| {
+        let (start, count) = (k / (batteries + 1), k % (batteries + 1));
+        if count == 0 {
+            return Some(0);
+        }
+        let take = memo
+            .get(&key(start + 1, count - 1))
+            .map(|rest| bank.0[start] as u64 * 10u64.pow(count as u32 - 1) + rest);
+        let skip = if len - start > count {
+            memo.get(&key(start + 1, count)).copied()
+        } else {
+            Some(0)
+        };
+        Some(take?.max(skip?))
+    });
+    *best.get(key(0, batteries))
 }
 
 #[aoc(day3, part1)]
@@ -71,6 +55,17 @@ pub fn part2(input: &[BatteryBank]) -> u64 {
     input.iter().map(|b| max_joltage(b, 12)).sum()
 }
 
+/// Sums the maximum joltage of every bank, reading only a single bank into memory at a time.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn total_joltage_streaming<R: BufRead>(
+    reader: R,
+    batteries: u32,
+) -> Result<u64, ReadError<()>> {
+    parse_lines_from(reader)
+        .map(|b| Ok(max_joltage(&b?, batteries)))
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,4 +85,13 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(&input_generator(INPUT)), 3121910778619);
     }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(total_joltage_streaming(INPUT.as_bytes(), 2).unwrap(), 357);
+        assert_eq!(
+            total_joltage_streaming(INPUT.as_bytes(), 12).unwrap(),
+            3121910778619
+        );
+    }
 }
@@ -1,63 +1,59 @@
-use crate::common::parse_lines;
+use crate::common::{
+    Interval, IntervalSet, ParseIntervalError, ReadError, parse_block_from, parse_lines_from,
+    parse_lines_strict,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
-use std::str::FromStr;
+use std::io::BufRead;
+use std::num::ParseIntError;
 
-#[derive(Debug, Copy, Clone)]
-pub struct Range(pub u64, pub u64);
-
-impl FromStr for Range {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once('-').ok_or(())?;
-        Ok(Self(
-            start.parse().map_err(|_| ())?,
-            end.parse().map_err(|_| ())?,
-        ))
-    }
-}
+#[cfg_attr(not(test), allow(dead_code))]
+type StreamError = ReadError<ParseIntervalError<ParseIntError>>;
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> (Vec<Range>, Vec<u64>) {
+pub fn input_generator(input: &str) -> (IntervalSet<u64>, Vec<u64>) {
     let (ranges, ids) = input.split_once("\n\n").unwrap();
-    (parse_lines(ranges).unwrap(), parse_lines(ids).unwrap())
+    (
+        parse_lines_strict::<Interval<u64>, _>(ranges).unwrap(),
+        parse_lines_strict(ids).unwrap(),
+    )
 }
 
 #[aoc(day5, part1)]
-pub fn part1(input: &(Vec<Range>, Vec<u64>)) -> usize {
-    input
-        .1
-        .iter()
-        .filter(|id| input.0.iter().any(|range| (range.0..=range.1).contains(id)))
-        .count()
+pub fn part1(input: &(IntervalSet<u64>, Vec<u64>)) -> usize {
+    input.1.iter().filter(|&&id| input.0.contains(id)).count()
 }
 
-#[aoc(day5, part2)]
-pub fn part2(input: &(Vec<Range>, Vec<u64>)) -> u64 {
-    let mut ranges = vec![];
-    let mut current = None;
-    for r in input.0.iter().copied().sorted_unstable_by_key(|r| r.0) {
-        if r.0 > r.1 {
-            continue;
-        }
-
-        let Some(cur) = &mut current else {
-            current = Some(r);
-            continue;
-        };
+/// Merges every range into the set as soon as it is read, so only the disjoint ranges are kept in memory.
+#[cfg_attr(not(test), allow(dead_code))]
+fn read_ranges<R: BufRead>(reader: R) -> Result<IntervalSet<u64>, StreamError> {
+    let mut ranges = IntervalSet::new();
+    for range in parse_block_from(reader) {
+        ranges.insert(range?);
+    }
+    Ok(ranges)
+}
 
-        debug_assert!(r.0 >= cur.0);
-        if r.0 <= (cur.1 + 1) {
-            cur.1 = cur.1.max(r.1);
-        } else {
-            ranges.push(*cur);
-            current = Some(r);
+/// Streams the ranges and ids, only the merged ranges are kept in memory.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn part1_streaming<R: BufRead>(mut reader: R) -> Result<usize, StreamError> {
+    let ranges = read_ranges(&mut reader)?;
+    let mut count = 0;
+    for id in parse_lines_from(reader) {
+        if ranges.contains(id.map_err(|e| e.map_parse(ParseIntervalError::ParseError))?) {
+            count += 1;
         }
     }
-    ranges.extend(current);
+    Ok(count)
+}
+
+#[aoc(day5, part2)]
+pub fn part2(input: &(IntervalSet<u64>, Vec<u64>)) -> u128 {
+    input.0.covered_len()
+}
 
-    ranges.into_iter().map(|r| r.1 - r.0 + 1).sum()
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn part2_streaming<R: BufRead>(reader: R) -> Result<u128, StreamError> {
+    Ok(read_ranges(reader)?.covered_len())
 }
 
 #[cfg(test)]
@@ -87,4 +83,10 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(&input_generator(INPUT)), 14);
     }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(part1_streaming(INPUT.as_bytes()).unwrap(), 3);
+        assert_eq!(part2_streaming(INPUT.as_bytes()).unwrap(), 14);
+    }
 }
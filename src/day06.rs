@@ -1,4 +1,4 @@
-use crate::common::parse_split_whitespace;
+use crate::common::{Arith, OverflowError, parse_split_whitespace};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::str::FromStr;
 
@@ -16,10 +16,10 @@ impl Op {
         }
     }
 
-    pub fn apply(&self, a: u64, b: u64) -> u64 {
+    pub fn apply(&self, a: u64, b: u64, arith: Arith) -> Result<u64, OverflowError> {
         match self {
-            Op::Add => a + b,
-            Op::Mul => a * b,
+            Op::Add => arith.add(a, b),
+            Op::Mul => arith.mul(a, b),
         }
     }
 }
@@ -52,16 +52,18 @@ pub fn input_generator_1(input: &str) -> (Vec<Vec<u64>>, Vec<Op>) {
 }
 
 #[aoc(day6, part1)]
-pub fn part1(input: &(Vec<Vec<u64>>, Vec<Op>)) -> u64 {
+pub fn part1(input: &(Vec<Vec<u64>>, Vec<Op>)) -> Result<u64, OverflowError> {
+    let arith = Arith::new(6, 1);
     let mut result = 0;
     for (i, op) in input.1.iter().enumerate() {
-        result += input
+        let problem = input
             .0
             .iter()
             .map(|l| l[i])
-            .fold(op.initial(), |a, e| op.apply(a, e));
+            .try_fold(op.initial(), |a, e| op.apply(a, e, arith))?;
+        result = arith.add(result, problem)?;
     }
-    result
+    Ok(result)
 }
 
 #[aoc_generator(day6, part2)]
@@ -80,7 +82,8 @@ pub fn input_generator_2(input: &str) -> (Vec<Vec<Option<u8>>>, Vec<Op>) {
 }
 
 #[aoc(day6, part2)]
-pub fn part2(input: &(Vec<Vec<Option<u8>>>, Vec<Op>)) -> u64 {
+pub fn part2(input: &(Vec<Vec<Option<u8>>>, Vec<Op>)) -> Result<u64, OverflowError> {
+    let arith = Arith::new(6, 2);
     let mut result = 0;
     let mut col = input.0[0].len();
     for op in input.1.iter().rev() {
@@ -92,21 +95,21 @@ pub fn part2(input: &(Vec<Vec<Option<u8>>>, Vec<Op>)) -> u64 {
             for l in &input.0 {
                 if let Some(d) = l[col] {
                     number_found = true;
-                    n = 10 * n + d as u64;
+                    n = arith.add(arith.mul(10, n)?, d as u64)?;
                 }
             }
 
             if number_found {
-                problem = op.apply(problem, n);
+                problem = op.apply(problem, n, arith)?;
                 if col > 0 {
                     continue;
                 }
             }
             break;
         }
-        result += problem;
+        result = arith.add(result, problem)?;
     }
-    result
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -124,11 +127,21 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&input_generator_1(INPUT)), 4277556);
+        assert_eq!(part1(&input_generator_1(INPUT)), Ok(4277556));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input_generator_2(INPUT)), 3263827);
+        assert_eq!(part2(&input_generator_2(INPUT)), Ok(3263827));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let input = "4294967296 1\n4294967296 2\n* +";
+        assert_eq!(
+            part1(&input_generator_1(input)),
+            Err(OverflowError { day: 6, part: 1 })
+        );
     }
 }
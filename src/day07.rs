@@ -1,4 +1,4 @@
-use crate::common::{Grid, Vec2i};
+use crate::common::{Answer, Arith, Grid, Memo, OverflowError, Vec2i};
 use aoc_runner_derive::{aoc, aoc_generator};
 use rustc_hash::FxHashSet;
 use std::collections::VecDeque;
@@ -58,13 +58,14 @@ pub fn part1(grid: &Grid<Tile>) -> usize {
 }
 
 #[aoc(day7, part2)]
-pub fn part2(grid: &Grid<Tile>) -> usize {
+pub fn part2(grid: &Grid<Tile>) -> Result<Answer, OverflowError> {
+    let arith = Arith::new(7, 2);
     let (start, _) = grid
         .pos_iter()
         .find(|(_, tile)| **tile == Tile::Start)
         .unwrap();
 
-    let mut g: Grid<usize> = Grid::new_from_element(grid.size_x, grid.size_y, 1);
+    let mut g: Grid<u128> = Grid::new_from_element(grid.size_x, grid.size_y, 1);
     let sx = grid.size_x as i64;
     let sy = grid.size_y as i64;
 
@@ -74,15 +75,50 @@ pub fn part2(grid: &Grid<Tile>) -> usize {
             if grid[p] == Tile::Splitter {
                 let l = p + Vec2i::new(-1, 1);
                 let r = p + Vec2i::new(1, 1);
-                g[p] =
-                    if g.in_bounds(&l) { g[l] } else { 0 } + if g.in_bounds(&r) { g[r] } else { 0 };
+                g[p] = arith.add(
+                    if g.in_bounds(&l) { g[l] } else { 0 },
+                    if g.in_bounds(&r) { g[r] } else { 0 },
+                )?;
             } else {
                 g[p] = g[p + Vec2i::new(0, 1)];
             }
         }
     }
 
-    g[start]
+    Ok(g[start].into())
+}
+
+#[aoc(day7, part2, Memo)]
+pub fn part2_memo(grid: &Grid<Tile>) -> Result<Answer, OverflowError> {
+    let arith = Arith::new(7, 2);
+    let (start, _) = grid
+        .pos_iter()
+        .find(|(_, tile)| **tile == Tile::Start)
+        .unwrap();
+
+    let sx = grid.size_x as i64;
+    let index = |p: Vec2i| (p.y * sx + p.x) as usize;
+    let mut timelines = Memo::dense(grid.size_x * grid.size_y, |&i, memo| {
+        let p = Vec2i::new(i as i64 % sx, i as i64 / sx);
+        let below = p + Vec2i::new(0, 1);
+        if !grid.in_bounds(&below) {
+            return Some(Ok(1u128));
+        }
+        if grid[p] != Tile::Splitter {
+            return memo.get(&index(below)).copied();
+        }
+
+        let mut count = Some(Ok(0));
+        for side in [below + Vec2i::new(-1, 0), below + Vec2i::new(1, 0)] {
+            if grid.in_bounds(&side) {
+                let c = memo.get(&index(side)).copied();
+                count = count.zip(c).map(|(a, b)| arith.add(a?, b?));
+            }
+        }
+        count
+    });
+
+    timelines.get(index(start)).map(Answer::from)
 }
 
 #[cfg(test)]
@@ -115,6 +151,11 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input_generator(INPUT)), 40);
+        assert_eq!(part2(&input_generator(INPUT)), Ok(40u64.into()));
+    }
+
+    #[test]
+    fn test_part2_memo() {
+        assert_eq!(part2_memo(&input_generator(INPUT)), Ok(40u64.into()));
     }
 }
@@ -1,4 +1,7 @@
-use crate::common::{ParseVecError, Vec3i, parse_lines, parse_vec};
+use crate::common::{
+    DisjointSet, EuclideanSquared, KdTree, Metric, ParseVecError, Vec3i, parse_lines_strict,
+    parse_vec,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use std::num::ParseIntError;
@@ -16,68 +19,33 @@ pub fn input_generator(input: &str) -> Vec<Vec3i> {
         }
     }
 
-    let v: Vec<I> = parse_lines(input).unwrap();
+    let v: Vec<I> = parse_lines_strict(input).unwrap();
     v.into_iter().map(|i| i.0).collect()
 }
 
-fn sq_dist(a: Vec3i, b: Vec3i) -> u64 {
-    a.x.abs_diff(b.x).pow(2) + a.y.abs_diff(b.y).pow(2) + a.z.abs_diff(b.z).pow(2)
-}
-
 #[aoc(day8, part1)]
 pub fn part1(input: &[Vec3i]) -> usize {
-    part1_impl(input, 1000)
+    part1_impl(input, 1000, EuclideanSquared)
 }
 
-pub fn part1_impl(input: &[Vec3i], mut max_connections: usize) -> usize {
+/// Connects the `max_connections` closest pairs of junction boxes, measuring their distance with `metric`.
+pub fn part1_impl(input: &[Vec3i], max_connections: usize, metric: impl Metric) -> usize {
     assert!(max_connections > 0);
-    let sorted_edges: Vec<_> = (0..input.len())
-        .tuple_combinations()
-        .sorted_unstable_by_key(|&(a, b)| sq_dist(input[a], input[b]))
-        .collect();
-
-    // very inefficient union-find data structure for minimum-spanning-tree
-    let mut circuits: Vec<_> = (0..input.len()).collect();
-    for (a, b) in sorted_edges {
-        let old_circuit = circuits[b];
-        let new_circuit = circuits[a];
-        if old_circuit != new_circuit {
-            circuits
-                .iter_mut()
-                .filter(|c| **c == old_circuit)
-                .for_each(|c| *c = new_circuit);
-        }
-
-        max_connections -= 1;
-        if max_connections == 0 {
-            break;
-        }
+    let mut circuits = DisjointSet::new(input.len());
+    let tree = KdTree::with_metric(input, metric);
+    for (a, b, _) in tree.closest_pairs().take(max_connections) {
+        circuits.union(a, b);
     }
 
-    let counts = circuits.into_iter().counts();
-    counts.values().k_largest(3).product()
+    circuits.component_sizes().k_largest(3).product()
 }
 
 #[aoc(day8, part2)]
 pub fn part2(input: &[Vec3i]) -> i64 {
-    let sorted_edges: Vec<_> = (0..input.len())
-        .tuple_combinations()
-        .sorted_unstable_by_key(|&(a, b)| sq_dist(input[a], input[b]))
-        .collect();
-
-    // very inefficient union-find data structure for minimum-spanning-tree
-    let mut circuits: Vec<_> = (0..input.len()).collect();
-    for (a, b) in sorted_edges {
-        let old_circuit = circuits[b];
-        let new_circuit = circuits[a];
-        if old_circuit != new_circuit {
-            circuits
-                .iter_mut()
-                .filter(|c| **c == old_circuit)
-                .for_each(|c| *c = new_circuit);
-            if circuits.iter().all_equal() {
-                return input[a].x * input[b].x;
-            }
+    let mut circuits = DisjointSet::new(input.len());
+    for (a, b, _) in KdTree::new(input).closest_pairs() {
+        if circuits.union(a, b) && circuits.component_count() == 1 {
+            return input[a].x * input[b].x;
         }
     }
 
@@ -112,7 +80,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1_impl(&input_generator(INPUT), 10), 40);
+        assert_eq!(
+            part1_impl(&input_generator(INPUT), 10, EuclideanSquared),
+            40
+        );
     }
 
     #[test]
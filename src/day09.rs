@@ -1,4 +1,7 @@
-use crate::common::{ParseVecError, Vec2i, parse_lines, parse_vec};
+use crate::common::{
+    Aabb2, CompressedGrid, Grid, ParseVecError, RectilinearPolygon, Vec2i, parse_lines_strict,
+    parse_vec,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use std::num::ParseIntError;
@@ -16,12 +19,15 @@ pub fn input_generator(input: &str) -> Vec<Vec2i> {
         }
     }
 
-    let v: Vec<I> = parse_lines(input).unwrap();
+    let v: Vec<I> = parse_lines_strict(input).unwrap();
     v.into_iter().map(|i| i.0).collect()
 }
 
 fn rectangle_area(a: Vec2i, b: Vec2i) -> u64 {
-    (a.x.abs_diff(b.x) + 1) * (a.y.abs_diff(b.y) + 1)
+    Aabb2::from_corners(a, b)
+        .area_inclusive()
+        .and_then(|area| u64::try_from(area).ok())
+        .expect("rectangle area overflows u64")
 }
 
 #[aoc(day9, part1)]
@@ -36,39 +42,54 @@ pub fn part1(input: &[Vec2i]) -> u64 {
 
 #[aoc(day9, part2)]
 pub fn part2(input: &[Vec2i]) -> u64 {
+    let polygon = RectilinearPolygon::new(input.to_vec()).unwrap();
     let mut max_area = 0;
-    'outer: for (&a, &b) in input.iter().tuple_combinations() {
-        let min_x = a.x.min(b.x);
-        let max_x = a.x.max(b.x);
-        let min_y = a.y.min(b.y);
-        let max_y = a.y.max(b.y);
-
+    for (&a, &b) in input.iter().tuple_combinations() {
         // optimization: calculate area of every rectangle
         // and prune it directly if it cannot be bigger than our current max
         let area = rectangle_area(a, b);
-        if area < max_area {
-            continue 'outer;
+        if area > max_area && polygon.contains_rect(a, b) {
+            max_area = area;
         }
+    }
+
+    max_area
+}
+
+/// Same as [`part2`], but answers every rectangle query in constant time
+/// by counting the outside cells of the compressed polygon with prefix sums.
+#[aoc(day9, part2, Compressed)]
+pub fn part2_compressed(input: &[Vec2i]) -> u64 {
+    let polygon = RectilinearPolygon::new(input.to_vec()).unwrap();
+    let compressed = CompressedGrid::rasterize_polygon(&polygon);
+
+    // outside[p] is the number of outside cells in the compressed rectangle [0, p)
+    let mut outside: Grid<u32> =
+        Grid::new_from_default(compressed.grid.size_x + 1, compressed.grid.size_y + 1);
+    for (pos, &inside) in compressed.grid.pos_iter() {
+        let p = pos + Vec2i::new(1, 1);
+        outside[p] = !inside as u32 + outside[p - Vec2i::new(1, 0)] + outside[p - Vec2i::new(0, 1)]
+            - outside[pos];
+    }
 
-        for (&start, &end) in input.iter().circular_tuple_windows() {
-            let start_x = start.x.min(end.x);
-            let end_x = start.x.max(end.x);
-            let start_y = start.y.min(end.y);
-            let end_y = start.y.max(end.y);
-
-            // check if the current edge [start-end] of the polygon intersects with the inside of the current rectangle
-            // we have to allow edges that lie on the border of the rectangle
-            // this is trivial, because the rectangle and the edge are axis-aligned,
-            // and we know that the edges of the rectangle are already inside the polygon by construction
-            // so we just care about the insides of the rectangle, every 2x2 and smaller rectangle is already valid
-            if end_x > min_x && start_x < max_x && end_y > min_y && start_y < max_y {
-                continue 'outer;
-            }
-
-            // TODO: also check if only the two corners intersects with the polygon and the between space is outside!
+    let mut max_area = 0;
+    for (&a, &b) in input.iter().tuple_combinations() {
+        let area = rectangle_area(a, b);
+        if area <= max_area {
+            continue;
         }
 
-        max_area = area;
+        let (a, b) = (
+            compressed.compress(a).unwrap(),
+            compressed.compress(b).unwrap(),
+        );
+        let (min, max) = (a.inf(&b), a.sup(&b) + Vec2i::new(1, 1));
+        let outside_cells = outside[max] + outside[min]
+            - outside[Vec2i::new(min.x, max.y)]
+            - outside[Vec2i::new(max.x, min.y)];
+        if outside_cells == 0 {
+            max_area = area;
+        }
     }
 
     max_area
@@ -93,8 +114,20 @@ mod tests {
         assert_eq!(part1(&input_generator(INPUT)), 50);
     }
 
+    #[test]
+    fn test_large_area() {
+        // the area fits into a u64, but not into an i64
+        let corner = Vec2i::new((1 << 33) - 1, (1 << 30) - 1);
+        assert_eq!(rectangle_area(Vec2i::zeros(), corner), 1 << 63);
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2(&input_generator(INPUT)), 24);
     }
+
+    #[test]
+    fn test_part2_compressed() {
+        assert_eq!(part2_compressed(&input_generator(INPUT)), 24);
+    }
 }
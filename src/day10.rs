@@ -1,11 +1,10 @@
-use crate::common::{parse_lines, parse_split};
-use aoc_runner_derive::{aoc, aoc_generator};
-use good_lp::{
-    Expression, ProblemVariables, Solution, SolverModel, constraint, default_solver, variable,
+use crate::common::{
+    BitSet, Gf2Matrix, IntegerProgram, ListFormat, ParseVecError, parse_lines_strict, parse_list,
 };
-use pathfinding::prelude::bfs;
+use aoc_runner_derive::{aoc, aoc_generator};
 use std::num::ParseIntError;
 use std::str::FromStr;
+use tinyvec::TinyVec;
 
 #[derive(Debug, Clone)]
 pub struct Machine {
@@ -36,40 +35,16 @@ impl FromStr for Machine {
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
 pub struct Lights {
-    state: u32,
+    state: BitSet,
 }
 
 impl FromStr for Lights {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_matches(['[', ']']);
-        let state: Vec<bool> = s.chars().map(|c| c == '#').collect();
-        let mut l = Self::default();
-        state
-            .iter()
-            .enumerate()
-            .filter(|(_, b)| **b)
-            .for_each(|(i, _)| l.toggle_index(i));
-        Ok(l)
-    }
-}
-
-impl Lights {
-    fn toggle_index(&mut self, index: usize) {
-        debug_assert!(index < u32::BITS as usize);
-        let mask = 1u32 << index;
-        if (self.state & mask) != 0 {
-            self.state &= !mask;
-        } else {
-            self.state |= mask;
-        }
-    }
-
-    pub fn toggle(&mut self, buttons: &Buttons) {
-        for &idx in &buttons.indices {
-            self.toggle_index(idx as usize);
-        }
+        Ok(Self {
+            state: s.parse().map_err(|_| ())?,
+        })
     }
 }
 
@@ -79,52 +54,46 @@ pub struct Joltages {
 }
 
 impl FromStr for Joltages {
-    type Err = ParseIntError;
+    type Err = ParseVecError<ParseIntError>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_matches(['{', '}']);
         Ok(Self {
-            state: parse_split(s, ',')?,
+            state: parse_list(s, &ListFormat::COMMA, 1..)?,
         })
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Buttons {
-    indices: Vec<u8>,
+    indices: TinyVec<[usize; 16]>,
 }
 
 impl FromStr for Buttons {
-    type Err = ParseIntError;
+    type Err = ParseVecError<ParseIntError>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_matches(['(', ')']);
         Ok(Self {
-            indices: parse_split(s, ',')?,
+            indices: parse_list(s, &ListFormat::COMMA, 1..)?,
         })
     }
 }
 
 #[aoc_generator(day10)]
 pub fn input_generator(input: &str) -> Vec<Machine> {
-    parse_lines(input).unwrap()
+    parse_lines_strict(input).unwrap()
 }
 
 fn fewest_button_presses_1(m: &Machine) -> usize {
-    let path = bfs(
-        &Lights::default(),
-        |s| {
-            let s = s.clone();
-            m.buttons.iter().map(move |b| {
-                let mut l = s.clone();
-                l.toggle(b);
-                l
-            })
-        },
-        |s| s == &m.lights,
-    )
-    .unwrap();
-    path.len() - 1
+    // pressing a button twice cancels out, so we look for the smallest set of button columns
+    // that sums up to the target lights over GF(2)
+    let matrix = Gf2Matrix::from_fn(m.lights.state.len(), m.buttons.len(), |light, button| {
+        m.buttons[button].indices.contains(&light)
+    });
+    let solutions = matrix.solve(&m.lights.state).unwrap();
+    let presses = solutions
+        .min_weight()
+        .expect("too many redundant buttons to try all combinations");
+    presses.count_ones() as usize
 }
 
 #[aoc(day10, part1)]
@@ -133,42 +102,19 @@ pub fn part1(input: &[Machine]) -> usize {
 }
 
 fn fewest_button_presses_2(m: &Machine) -> usize {
-    let mut problem = ProblemVariables::new();
-    let vars: Vec<_> = m
-        .buttons
-        .iter()
-        .map(|_| problem.add(variable().integer().min(0)))
-        .collect();
-
-    let objective = vars.iter().fold(Expression::default(), |a, e| a + e);
-    let constraints: Vec<_> = m
-        .joltages
-        .state
-        .iter()
-        .enumerate()
-        .map(|(idx, &j)| {
-            let expr =
-                m.buttons
-                    .iter()
-                    .zip(vars.iter())
-                    .fold(Expression::default(), |a, (b, &v)| {
-                        a + v * if b.indices.contains(&(idx as u8)) {
-                            1
-                        } else {
-                            0
-                        }
-                    });
-            constraint!(j as i32 == expr)
-        })
-        .collect();
-
-    let sol = problem
-        .minimise(objective.clone())
-        .using(default_solver)
-        .with_all(constraints)
-        .solve()
-        .unwrap();
-    sol.eval(&objective) as usize
+    // minimize the total number of presses, every joltage counts the presses of all buttons connected to it
+    let mut program = IntegerProgram::new(vec![1; m.buttons.len()]);
+    for (idx, &j) in m.joltages.state.iter().enumerate() {
+        let connected = m
+            .buttons
+            .iter()
+            .map(|b| b.indices.contains(&idx) as i64)
+            .collect();
+        program.add_equality(connected, j as i64);
+    }
+    let solution = program.solve().unwrap();
+    debug_assert!(program.verify(&solution));
+    solution.objective as usize
 }
 
 #[aoc(day10, part2)]
@@ -194,4 +140,21 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(&input_generator(INPUT)), 33);
     }
+
+    #[test]
+    fn test_part2_exact() {
+        let machine = input_generator(
+            "[.........] (0,3,4,5,6) (5) (3) (0,7) (6,7) (1,5,8) (1,2,7) (0,3,8) (5,7) (1,3,5,7) (0,2,4,7,8) (3,4) (0,4,5) {118,38,44,41,58,64,44,123,98}",
+        );
+        assert_eq!(part2(&machine), 197);
+    }
+
+    #[test]
+    fn test_wide_machine() {
+        // light 300 must not be confused with light 300 % 256
+        let lights = format!("[{}#]", ".".repeat(300));
+        let joltages = vec!["0"; 301].join(",");
+        let machine = input_generator(&format!("{lights} (44) (0,300) (0) {{{joltages}}}"));
+        assert_eq!(part1(&machine), 2);
+    }
 }
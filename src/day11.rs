@@ -1,10 +1,21 @@
-use crate::common::{parse_lines, parse_split_whitespace};
+use crate::common::{
+    Answer, Arith, Between, CyclicDependency, Digraph, GraphView, Memo, OverflowError,
+    articulation_nodes, mandatory_nodes, parse_lines_strict, parse_split_whitespace,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
+use num::BigInt;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::str::FromStr;
+use thiserror::Error;
 
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> FxHashMap<String, FxHashSet<String>> {
+pub fn input_generator(input: &str) -> Digraph {
+    input.parse().unwrap()
+}
+
+/// Parses into a map of owned strings, only kept to benchmark against [`input_generator`].
+#[aoc_generator(day11, part1, Owned)]
+pub fn input_generator_owned(input: &str) -> FxHashMap<String, FxHashSet<String>> {
     struct I(String, Vec<String>);
 
     impl FromStr for I {
@@ -19,68 +30,110 @@ pub fn input_generator(input: &str) -> FxHashMap<String, FxHashSet<String>> {
         }
     }
 
-    let v: Vec<I> = parse_lines(input).unwrap();
+    let v: Vec<I> = parse_lines_strict(input).unwrap();
     v.into_iter()
         .map(|i| (i.0, FxHashSet::from_iter(i.1)))
         .collect()
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    #[error(transparent)]
+    Overflow(#[from] OverflowError),
+    /// A cycle between the start and the end, which allows infinitely many paths.
+    #[error("the paths between the start and the end loop through {0}")]
+    Cycle(String),
+}
+
 fn count_paths_from_to(
-    input: &FxHashMap<String, FxHashSet<String>>,
+    devices: &Digraph,
     from: &str,
     to: &str,
-) -> usize {
-    pathfinding::prelude::count_paths(
-        from,
-        |device| input.get(*device).into_iter().flatten().map(|s| s.as_str()),
-        |device| *device == to,
-    )
+    avoid: &[&str],
+    arith: Arith,
+) -> Result<usize, PathError> {
+    let (Some(from), Some(to)) = (devices.id(from), devices.id(to)) else {
+        return Ok(0);
+    };
+    // paths may start at an avoided device, but never lead into one
+    let avoid: Vec<_> = avoid.iter().filter_map(|&name| devices.id(name)).collect();
+    let allowed = devices.subgraph(|_| true, |_, to| !avoid.contains(&to));
+    let between = Between::new(&allowed, from, to);
+    let mut paths = Memo::dense(between.node_bound(), |&device, memo| {
+        // look up every next device before giving up, so the missing ones are computed together
+        let counts: Vec<_> = between
+            .successors(device as u32)
+            .map(|next| memo.get(&(next as usize)).copied())
+            .collect();
+        let counts: Vec<_> = counts.into_iter().collect::<Option<_>>()?;
+        Some(
+            counts
+                .into_iter()
+                .try_fold((device == to as usize) as usize, |count, c| {
+                    arith.add(count, c?)
+                }),
+        )
+    });
+    match paths.try_get(from as usize) {
+        Ok(count) => Ok((*count)?),
+        Err(CyclicDependency(device)) => Err(PathError::Cycle(devices.name(device as u32).into())),
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+fn named<'a>(
+    devices: &'a Digraph,
+    query: fn(&Digraph, u32, u32) -> Vec<u32>,
+    from: &str,
+    to: &str,
+) -> Vec<&'a str> {
+    let (Some(from), Some(to)) = (devices.id(from), devices.id(to)) else {
+        return vec![];
+    };
+    query(devices, from, to)
+        .into_iter()
+        .map(|d| devices.name(d))
+        .collect()
+}
+
+/// The devices on every path from `from` to `to` in path order, including both ends.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn mandatory_devices<'a>(devices: &'a Digraph, from: &str, to: &str) -> Vec<&'a str> {
+    named(devices, mandatory_nodes, from, to)
+}
+
+/// The devices whose single failure disconnects `to` from `from`.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn articulation_devices<'a>(devices: &'a Digraph, from: &str, to: &str) -> Vec<&'a str> {
+    named(devices, articulation_nodes, from, to)
 }
 
 #[aoc(day11, part1)]
-pub fn part1(input: &FxHashMap<String, FxHashSet<String>>) -> usize {
-    count_paths_from_to(input, "you", "out")
+pub fn part1(devices: &Digraph) -> Result<usize, PathError> {
+    count_paths_from_to(devices, "you", "out", &[], Arith::new(11, 1))
+}
+
+#[aoc(day11, part1, Owned)]
+pub fn part1_owned(input: &FxHashMap<String, FxHashSet<String>>) -> usize {
+    pathfinding::prelude::count_paths(
+        "you",
+        |device| input.get(*device).into_iter().flatten().map(|s| s.as_str()),
+        |device| *device == "out",
+    )
 }
 
 #[aoc(day11, part2)]
-pub fn part2(input: &FxHashMap<String, FxHashSet<String>>) -> usize {
-    let input_without_fft: FxHashMap<String, FxHashSet<String>> = input
-        .iter()
-        .map(|(k, v)| {
-            (
-                k.clone(),
-                v.iter().filter(|s| *s != "fft").cloned().collect(),
-            )
-        })
-        .collect();
-    let input_without_dac = input
-        .iter()
-        .map(|(k, v)| {
-            (
-                k.clone(),
-                v.iter().filter(|s| *s != "dac").cloned().collect(),
-            )
-        })
-        .collect();
-    let input_without_dac_and_fft = input
-        .iter()
-        .map(|(k, v)| {
-            (
-                k.clone(),
-                v.iter()
-                    .filter(|s| *s != "dac" && *s != "fft")
-                    .cloned()
-                    .collect(),
-            )
-        })
-        .collect();
-    let a1 = count_paths_from_to(&input_without_fft, "svr", "dac");
-    let a2 = count_paths_from_to(&input_without_dac, "dac", "fft");
-    let a3 = count_paths_from_to(&input_without_dac_and_fft, "fft", "out");
-    let b1 = count_paths_from_to(&input_without_dac, "svr", "fft");
-    let b2 = count_paths_from_to(&input_without_fft, "fft", "dac");
-    let b3 = count_paths_from_to(&input_without_dac_and_fft, "dac", "out");
-    a1 * a2 * a3 + b1 * b2 * b3
+pub fn part2(devices: &Digraph) -> Result<Answer, PathError> {
+    let arith = Arith::new(11, 2);
+    let a1 = count_paths_from_to(devices, "svr", "dac", &["fft"], arith)?;
+    let a2 = count_paths_from_to(devices, "dac", "fft", &["dac"], arith)?;
+    let a3 = count_paths_from_to(devices, "fft", "out", &["dac", "fft"], arith)?;
+    let b1 = count_paths_from_to(devices, "svr", "fft", &["dac"], arith)?;
+    let b2 = count_paths_from_to(devices, "fft", "dac", &["fft"], arith)?;
+    let b3 = count_paths_from_to(devices, "dac", "out", &["dac", "fft"], arith)?;
+    // the products can overflow 64 bits even if the individual path counts don't
+    let product = |a, b, c| BigInt::from(a) * b * c;
+    Ok((product(a1, a2, a3) + product(b1, b2, b3)).into())
 }
 
 #[cfg(test)]
@@ -115,11 +168,53 @@ hhh: out"#;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&input_generator(INPUT)), 5);
+        assert_eq!(part1(&input_generator(INPUT)), Ok(5));
+    }
+
+    #[test]
+    fn test_part1_owned() {
+        assert_eq!(part1_owned(&input_generator_owned(INPUT)), 5);
+    }
+
+    #[test]
+    fn test_part1_unrelated_cycle() {
+        let devices = input_generator("you: out\naaa: bbb\nbbb: aaa");
+        assert_eq!(part1(&devices), Ok(1));
+    }
+
+    #[test]
+    fn test_part1_cycle() {
+        let devices = input_generator("you: aaa\naaa: bbb\nbbb: aaa out");
+        assert_eq!(part1(&devices), Err(PathError::Cycle("aaa".into())));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input_generator(INPUT2)), 2);
+        assert_eq!(part2(&input_generator(INPUT2)), Ok(2u64.into()));
+    }
+
+    #[test]
+    fn test_part2_cycle_through_start() {
+        // every leg starting at an avoided device must not lead back into it
+        let devices = input_generator("svr: dac\ndac: fft\nfft: dac out");
+        assert_eq!(part2(&devices), Ok(1u64.into()));
+    }
+
+    #[test]
+    fn test_mandatory_devices() {
+        let devices = input_generator(INPUT2);
+        assert_eq!(
+            mandatory_devices(&devices, "svr", "out"),
+            ["svr", "ccc", "fff", "out"]
+        );
+        assert_eq!(articulation_devices(&devices, "svr", "out"), ["ccc", "fff"]);
+        assert_eq!(
+            articulation_devices(&devices, "ccc", "fff"),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            mandatory_devices(&devices, "out", "svr"),
+            Vec::<&str>::new()
+        );
     }
 }
@@ -1,7 +1,9 @@
-use crate::common::{parse_lines, parse_split_whitespace, Grid};
+use crate::common::{Dlx, Grid, Vec2i, parse_lines_strict, parse_split_whitespace};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use std::iter;
 use std::str::FromStr;
+use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tile {
@@ -32,6 +34,39 @@ impl Shape {
         let block_count = grid.iter().filter(|&&t| t == Tile::Block).count();
         Self { grid, block_count }
     }
+
+    /// The blocks of all distinct rotations and reflections, each moved to touch the origin.
+    fn orientations(&self) -> Vec<Vec<Vec2i>> {
+        let blocks = self
+            .grid
+            .pos_iter()
+            .filter(|(_, t)| **t == Tile::Block)
+            .map(|(p, _)| p)
+            .collect_vec();
+        let mut orientations: Vec<Vec<Vec2i>> = vec![];
+        for flip in [false, true] {
+            for rotations in 0..4 {
+                let mut o = blocks
+                    .iter()
+                    .map(|&p| {
+                        let p = if flip { Vec2i::new(-p.x, p.y) } else { p };
+                        (0..rotations).fold(p, |p, _| Vec2i::new(-p.y, p.x))
+                    })
+                    .collect_vec();
+                let min = o
+                    .iter()
+                    .copied()
+                    .reduce(|a, e| a.inf(&e))
+                    .unwrap_or_default();
+                o.iter_mut().for_each(|p| *p -= min);
+                o.sort_unstable_by_key(|p| (p.y, p.x));
+                if !orientations.contains(&o) {
+                    orientations.push(o);
+                }
+            }
+        }
+        orientations
+    }
 }
 
 impl FromStr for Shape {
@@ -79,42 +114,103 @@ pub fn input_generator(input: &str) -> (Vec<Shape>, Vec<Region>) {
         .map(|s| s.parse())
         .collect::<Result<_, _>>()
         .unwrap();
-    let regions = parse_lines(regions).unwrap();
+    let regions = parse_lines_strict(regions).unwrap();
     (shapes, regions)
 }
 
-fn fits(region: &Region, shapes: &[Shape]) -> bool {
+/// The maximum number of placements tried for a single region.
+const FITS_NODE_BUDGET: u64 = 10_000_000;
+
+/// The search for a region ran out of its node budget before deciding if the pieces fit.
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[error("gave up on a {width}x{height} region after {nodes} placements")]
+pub struct UndecidedRegion {
+    width: u8,
+    height: u8,
+    nodes: u64,
+}
+
+fn fits(region: &Region, shapes: &[Shape], budget: u64) -> Result<bool, UndecidedRegion> {
     let region_size = region.width as usize * region.height as usize;
-    let max_required_space: usize = region
-        .shapes
-        .iter()
-        .enumerate()
-        .map(|(i, &amount)| amount as usize * shapes[i].grid.size_x * shapes[i].grid.size_y)
-        .sum();
+    let piece_count: usize = region.shapes.iter().map(|&amount| amount as usize).sum();
     let min_required_space: usize = region
         .shapes
         .iter()
         .enumerate()
         .map(|(i, &amount)| amount as usize * shapes[i].block_count)
         .sum();
-    if region_size >= max_required_space {
-        return true;
+
+    // every piece fits into its own slot of the largest bounding box
+    let slot_x = shapes
+        .iter()
+        .map(|s| s.grid.size_x)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let slot_y = shapes
+        .iter()
+        .map(|s| s.grid.size_y)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let slots = (region.width as usize / slot_x) * (region.height as usize / slot_y);
+    if slots >= piece_count {
+        return Ok(true);
     } else if region_size < min_required_space {
-        return false;
+        return Ok(false);
+    }
+
+    // exact cover: every shape has to be placed as often as required, every cell may be covered at most once
+    let (width, height) = (region.width as i64, region.height as i64);
+    let required = region
+        .shapes
+        .iter()
+        .enumerate()
+        .filter(|&(_, &amount)| amount > 0)
+        .collect_vec();
+    let cell = |p: Vec2i| required.len() + (p.y * width + p.x) as usize;
+    let mut dlx = Dlx::new(required.len(), region_size).with_node_budget(budget);
+    let orientations = shapes.iter().map(Shape::orientations).collect_vec();
+    for (column, &(shape, &amount)) in required.iter().enumerate() {
+        // copies of a shape are interchangeable, so they only need to be placed in one order
+        dlx.set_multiplicity(column, amount as usize);
+        for o in &orientations[shape] {
+            let size = o.iter().fold(Vec2i::zeros(), |a, e| a.sup(e));
+            for y in 0..height - size.y {
+                for x in 0..width - size.x {
+                    let offset = Vec2i::new(x, y);
+                    let columns = iter::once(column)
+                        .chain(o.iter().map(|&p| cell(p + offset)))
+                        .collect_vec();
+                    dlx.add_row(&columns);
+                }
+            }
+        }
     }
 
-    // TODO: actually check this?
-    // seems to just work for my input, but not for the example...
-    unimplemented!("{min_required_space}-{max_required_space} for region {region_size}");
+    dlx.first_solution()
+        .map(|solution| solution.is_some())
+        .map_err(|stats| UndecidedRegion {
+            width: region.width,
+            height: region.height,
+            nodes: stats.nodes,
+        })
 }
 
 #[aoc(day12, part1)]
-pub fn part1(input: &(Vec<Shape>, Vec<Region>)) -> usize {
-    input.1.iter().filter(|r| fits(r, &input.0)).count()
+pub fn part1(input: &(Vec<Shape>, Vec<Region>)) -> Result<usize, UndecidedRegion> {
+    input
+        .1
+        .iter()
+        .map(|r| fits(r, &input.0, FITS_NODE_BUDGET).map(usize::from))
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
     const INPUT: &str = r#"0:
 ###
 ##.
@@ -151,6 +247,19 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        // TODO: assert_eq!(part1(&input_generator(INPUT)), 2);
+        assert_eq!(part1(&input_generator(INPUT)), Ok(2));
+    }
+
+    #[test]
+    fn test_budget_exceeded() {
+        let (shapes, regions) = input_generator(INPUT);
+        assert_eq!(
+            fits(&regions[1], &shapes, 1),
+            Err(UndecidedRegion {
+                width: 12,
+                height: 5,
+                nodes: 1
+            })
+        );
     }
 }